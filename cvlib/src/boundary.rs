@@ -0,0 +1,180 @@
+use alloc::string::{String, ToString};
+
+#[cfg(feature = "std")]
+use crate::correlationvectorinbounderror::CorrelationVectorInboundError;
+use crate::{
+    correlationvector::CorrelationVector, correlationvectorversion::CorrelationVectorVersion,
+};
+
+/// The name of the header correlation vectors are propagated in
+pub const CORRELATION_VECTOR_HEADER: &str = "MS-CV";
+
+/// How an inbound correlation vector is handled at a service boundary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InboundPolicy {
+    /// Whether the inbound correlation vector is parsed with
+    /// [`CorrelationVector::parse_strict`] or [`CorrelationVector::parse`]
+    pub strict: bool,
+    /// What to do with an inbound correlation vector that can not be parsed
+    pub on_invalid: InvalidInbound,
+    /// What to do with an inbound correlation vector that is terminated
+    pub on_terminated: TerminatedInbound,
+    /// The version of the correlation vector created when there is none to continue
+    pub new_version: CorrelationVectorVersion,
+}
+
+impl Default for InboundPolicy {
+    /// Parse strictly, start a new v2 correlation vector for invalid input and propagate
+    /// terminated input unchanged, as the specification recommends
+    fn default() -> Self {
+        InboundPolicy {
+            strict: true,
+            on_invalid: InvalidInbound::CreateNew,
+            on_terminated: TerminatedInbound::Propagate,
+            new_version: CorrelationVectorVersion::V2,
+        }
+    }
+}
+
+/// What to do with an inbound correlation vector that can not be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidInbound {
+    /// Start a new correlation vector
+    CreateNew,
+    /// Return [`CorrelationVectorInboundError::Invalid`]
+    Reject,
+}
+
+/// What to do with an inbound correlation vector that is terminated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminatedInbound {
+    /// Keep using the terminated correlation vector, so the events stay correlated
+    Propagate,
+    /// Start a new correlation vector
+    CreateNew,
+    /// Return [`CorrelationVectorInboundError::Terminated`]
+    Reject,
+}
+
+impl CorrelationVector {
+    /// Continue the correlation vector received with an inbound request, following the
+    /// [`InboundPolicy::default`] rules.
+    ///
+    /// The inbound correlation vector is extended, so this service's events are its children.
+    /// A new correlation vector is created if the header is missing or invalid.
+    #[cfg(feature = "std")]
+    pub fn from_inbound(header: Option<&str>) -> CorrelationVector {
+        Self::from_inbound_with(header, InboundPolicy::default())
+            .expect("Default inbound policy does not reject input")
+    }
+
+    /// Continue the correlation vector received with an inbound request, following the given
+    /// policy for invalid and terminated input.
+    /// A new correlation vector is created if the header is missing.
+    #[cfg(feature = "std")]
+    pub fn from_inbound_with(
+        header: Option<&str>,
+        policy: InboundPolicy,
+    ) -> Result<CorrelationVector, CorrelationVectorInboundError> {
+        let header = match header {
+            Some(header) => header,
+            None => return Ok(CorrelationVector::new_with_version(policy.new_version)),
+        };
+
+        let parsed = if policy.strict {
+            CorrelationVector::parse_strict(header)
+        } else {
+            CorrelationVector::parse(header)
+        };
+        let mut cv = match (parsed, policy.on_invalid) {
+            (Ok(cv), _) => cv,
+            (Err(_), InvalidInbound::CreateNew) => {
+                return Ok(CorrelationVector::new_with_version(policy.new_version))
+            }
+            (Err(e), InvalidInbound::Reject) => return Err(e.into()),
+        };
+
+        if cv.is_immutable() {
+            match policy.on_terminated {
+                TerminatedInbound::Propagate => return Ok(cv),
+                TerminatedInbound::CreateNew => {
+                    return Ok(CorrelationVector::new_with_version(policy.new_version))
+                }
+                TerminatedInbound::Reject => return Err(CorrelationVectorInboundError::Terminated),
+            }
+        }
+
+        cv.extend();
+        Ok(cv)
+    }
+
+    /// Increment the correlation vector for an outbound request and return the value to send
+    /// in the [`CORRELATION_VECTOR_HEADER`]
+    pub fn outbound(&mut self) -> String {
+        self.increment();
+        self.as_str().to_string()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inbound_extends() {
+        let cv = CorrelationVector::from_inbound(Some("P9v1ltK2S7qTS77z0lWtKg.1"));
+        assert_eq!(cv.to_string(), "P9v1ltK2S7qTS77z0lWtKg.1.0");
+    }
+
+    #[test]
+    fn inbound_missing_or_invalid_creates_new() {
+        for header in [None, Some("garbage"), Some("P9v1ltK2S7qTS77z0lWtKg.01")] {
+            let cv = CorrelationVector::from_inbound(header);
+            assert_eq!(cv.version(), CorrelationVectorVersion::V2);
+            assert!(cv.to_string().ends_with(".0"));
+            assert_eq!(cv.to_string().split('.').count(), 2);
+        }
+    }
+
+    #[test]
+    fn inbound_terminated() {
+        let header = Some("P9v1ltK2S7qTS77z0lWtKg.1!");
+        let cv = CorrelationVector::from_inbound(header);
+        assert_eq!(cv.to_string(), "P9v1ltK2S7qTS77z0lWtKg.1!");
+
+        let policy = InboundPolicy {
+            on_terminated: TerminatedInbound::Reject,
+            ..InboundPolicy::default()
+        };
+        assert!(matches!(
+            CorrelationVector::from_inbound_with(header, policy),
+            Err(CorrelationVectorInboundError::Terminated)
+        ));
+    }
+
+    #[test]
+    fn inbound_invalid_rejected() {
+        let policy = InboundPolicy {
+            on_invalid: InvalidInbound::Reject,
+            ..InboundPolicy::default()
+        };
+        assert!(matches!(
+            CorrelationVector::from_inbound_with(Some("base.0"), policy),
+            Err(CorrelationVectorInboundError::Invalid { .. })
+        ));
+
+        let lenient = InboundPolicy {
+            strict: false,
+            ..policy
+        };
+        let cv = CorrelationVector::from_inbound_with(Some("base.0"), lenient).unwrap();
+        assert_eq!(cv.to_string(), "base.0.0");
+    }
+
+    #[test]
+    fn outbound_increments() {
+        let mut cv = CorrelationVector::from_inbound(Some("P9v1ltK2S7qTS77z0lWtKg.1"));
+        assert_eq!(cv.outbound(), "P9v1ltK2S7qTS77z0lWtKg.1.1");
+        assert_eq!(cv.outbound(), "P9v1ltK2S7qTS77z0lWtKg.1.2");
+    }
+}
@@ -0,0 +1,203 @@
+use alloc::vec::Vec;
+use core::{convert::TryFrom, iter};
+
+use crate::{
+    correlationvector::{serialized_length_of, CorrelationVector},
+    correlationvectoroperationerror::CorrelationVectorOperationError,
+};
+
+/// An iterator over the children of a correlation vector, `.0`, `.1`, `.2`, ..., that ends
+/// when the next child would exceed the length limit. See [`CorrelationVector::children`].
+#[derive(Debug, Clone)]
+pub struct Children<'a> {
+    parent: &'a CorrelationVector,
+    next: Option<u32>,
+}
+
+impl Iterator for Children<'_> {
+    type Item = CorrelationVector;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let segment = self.next?;
+        match self.parent.child_with(segment) {
+            Ok(child) => {
+                self.next = segment.checked_add(1);
+                Some(child)
+            }
+            Err(_) => {
+                self.next = None;
+                None
+            }
+        }
+    }
+}
+
+/// Unlike [`CorrelationVector::extend`] and [`CorrelationVector::increment`], these operations
+/// leave the correlation vector unchanged and return new ones. If a new correlation vector does
+/// not fit, an error is returned and the [`OverflowPolicy`](crate::OverflowPolicy) is not
+/// applied. The new correlation vectors keep the overflow policy but not the predecessor.
+impl CorrelationVector {
+    /// The first child of this correlation vector, as [`CorrelationVector::extend`] creates it,
+    /// e.g. `base.1.0` for `base.1`
+    pub fn child(&self) -> Result<CorrelationVector, CorrelationVectorOperationError> {
+        self.child_with(0)
+    }
+
+    /// The next sibling of this correlation vector, as [`CorrelationVector::increment`] creates
+    /// it, e.g. `base.1.3` for `base.1.2`
+    pub fn next_sibling(&self) -> Result<CorrelationVector, CorrelationVectorOperationError> {
+        if self.is_immutable() {
+            return Err(CorrelationVectorOperationError::Terminated);
+        }
+        let depth = self.depth();
+        let last = self
+            .segments()
+            .last()
+            .expect("Vector clock has at least one segment");
+        let next = last
+            .checked_add(1)
+            .ok_or(CorrelationVectorOperationError::ClockOverflow)?;
+        self.check_fits(serialized_length_of(next) - serialized_length_of(last))?;
+        Ok(self.derive(self.segments().take(depth - 1).chain(iter::once(next))))
+    }
+
+    /// The first `n` children of this correlation vector, e.g. for `n` parallel calls.
+    /// Returns an error instead if they do not all fit.
+    pub fn fork(
+        &self,
+        n: usize,
+    ) -> Result<Vec<CorrelationVector>, CorrelationVectorOperationError> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        let last =
+            u32::try_from(n - 1).map_err(|_| CorrelationVectorOperationError::ClockOverflow)?;
+        // the last child is the longest
+        self.child_with(last)?;
+        Ok(self.children().take(n).collect())
+    }
+
+    /// The children of this correlation vector, `.0`, `.1`, `.2`, ..., as long as they fit in
+    /// the length limit. A terminated correlation vector has no children.
+    pub fn children(&self) -> Children<'_> {
+        Children {
+            parent: self,
+            next: if self.is_immutable() { None } else { Some(0) },
+        }
+    }
+
+    fn child_with(
+        &self,
+        segment: u32,
+    ) -> Result<CorrelationVector, CorrelationVectorOperationError> {
+        if self.is_immutable() {
+            return Err(CorrelationVectorOperationError::Terminated);
+        }
+        self.check_fits(serialized_length_of(segment) + 1)?;
+        Ok(self.derive(self.segments().chain(iter::once(segment))))
+    }
+
+    fn check_fits(&self, required: usize) -> Result<(), CorrelationVectorOperationError> {
+        let remaining = self.remaining_capacity();
+        if required > remaining {
+            return Err(CorrelationVectorOperationError::Overflow {
+                required,
+                remaining,
+            });
+        }
+        Ok(())
+    }
+
+    /// A correlation vector with the same base, reset marker and settings but other segments
+    fn derive(&self, segments: impl IntoIterator<Item = u32>) -> CorrelationVector {
+        CorrelationVector::from_parts(
+            self.version(),
+            self.base(),
+            self.reset_marker(),
+            segments,
+            false,
+        )
+        .with_settings_of(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString, vec};
+
+    use super::*;
+    use crate::testutil::cv;
+
+    #[test]
+    fn child_and_next_sibling() {
+        let parent = cv("P9v1ltK2S7qTS77z0lWtKg.1.9");
+        assert_eq!(parent.child(), Ok(cv("P9v1ltK2S7qTS77z0lWtKg.1.9.0")));
+        assert_eq!(parent.next_sibling(), Ok(cv("P9v1ltK2S7qTS77z0lWtKg.1.10")));
+        assert_eq!(parent, cv("P9v1ltK2S7qTS77z0lWtKg.1.9"));
+
+        let terminated = cv("P9v1ltK2S7qTS77z0lWtKg.1!");
+        assert_eq!(
+            terminated.child(),
+            Err(CorrelationVectorOperationError::Terminated)
+        );
+        assert_eq!(
+            terminated.next_sibling(),
+            Err(CorrelationVectorOperationError::Terminated)
+        );
+        assert_eq!(
+            cv("P9v1ltK2S7qTS77z0lWtKg.4294967295").next_sibling(),
+            Err(CorrelationVectorOperationError::ClockOverflow)
+        );
+    }
+
+    #[test]
+    fn child_checks_length() {
+        let full = cv(&format!("P9v1ltK2S7qTS77z0lWtKg{}.9", ".0".repeat(51)));
+        assert_eq!(full.remaining_capacity(), 1);
+        assert_eq!(
+            full.child(),
+            Err(CorrelationVectorOperationError::Overflow {
+                required: 2,
+                remaining: 1
+            })
+        );
+        assert!(full.children().next().is_none());
+
+        // 9 becomes 10, which takes up the last character
+        let sibling = full.next_sibling().unwrap();
+        assert_eq!(sibling.remaining_capacity(), 0);
+        assert_eq!(
+            sibling.next_sibling(),
+            Ok(cv(&format!("P9v1ltK2S7qTS77z0lWtKg{}.11", ".0".repeat(51))))
+        );
+    }
+
+    #[test]
+    fn fork_and_children() {
+        let parent = cv("P9v1ltK2S7qTS77z0lWtKg.1");
+        let children = parent.fork(3).unwrap();
+        assert_eq!(
+            children.iter().map(|cv| cv.to_string()).collect::<Vec<_>>(),
+            vec![
+                "P9v1ltK2S7qTS77z0lWtKg.1.0",
+                "P9v1ltK2S7qTS77z0lWtKg.1.1",
+                "P9v1ltK2S7qTS77z0lWtKg.1.2"
+            ]
+        );
+        assert_eq!(parent.fork(0), Ok(vec![]));
+        assert!(children.iter().all(|child| parent.is_ancestor_of(child)));
+
+        // 125 characters leave room for children .0 to .9 only
+        let nearly_full = cv(&format!("P9v1ltK2S7qTS77z0lWtKg{}.10", ".0".repeat(50)));
+        assert_eq!(nearly_full.children().count(), 10);
+        assert_eq!(nearly_full.fork(10).unwrap().len(), 10);
+        assert_eq!(
+            nearly_full.fork(11),
+            Err(CorrelationVectorOperationError::Overflow {
+                required: 3,
+                remaining: 2
+            })
+        );
+        assert!(cv("P9v1ltK2S7qTS77z0lWtKg.1!").children().next().is_none());
+    }
+}
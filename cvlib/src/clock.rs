@@ -0,0 +1,30 @@
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+/// A source of the current time, used by [`CorrelationVector::spin_with`](crate::CorrelationVector::spin_with())
+/// and [`CorrelationVector::reset_with`](crate::CorrelationVector::reset_with())
+pub trait Clock {
+    /// The time elapsed since the UNIX epoch
+    fn now(&self) -> Duration;
+}
+
+/// The system's wall clock, used by default
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time is before the 0 epoch")
+    }
+}
+
+impl<F: Fn() -> Duration> Clock for F {
+    fn now(&self) -> Duration {
+        self()
+    }
+}
@@ -1,186 +1,861 @@
-use std::{
+use alloc::{boxed::Box, vec::Vec};
+use core::{
     convert::TryFrom,
-    fmt::{Display, Formatter},
-    num::ParseIntError,
-    time::SystemTime,
+    fmt::{Display, Formatter, Write},
 };
 
+use arrayvec::{ArrayString, ArrayVec};
 use uuid::Uuid;
 
 use crate::{
+    clock::Clock,
+    correlationvectoroperationerror::CorrelationVectorOperationError,
     correlationvectorparsererror::CorrelationVectorParseError,
-    spinparams::{generate_entropy, tick_periodicity_bits, ticks_to_drop, SpinParams},
+    correlationvectorref::CorrelationVectorRef,
+    correlationvectorversion::CorrelationVectorVersion,
+    decodedspin::{decode_spin, DecodedSpin},
+    entropysource::EntropySource,
+    overflowpolicy::OverflowPolicy,
+    spinlayout::SpinLayout,
+    spinparams::{epoch_offset_ticks, generate_entropy, low_bits_mask, next_monotonic, SpinMode},
 };
+#[cfg(feature = "std")]
+use crate::{clock::SystemClock, entropysource::RandomEntropy};
 
-const TERMINATION_SYMBOL: &str = "!";
+pub(crate) const TERMINATION_SYMBOL: &str = "!";
+const V3_VERSION_SYMBOL: char = 'A';
+pub(crate) const RESET_SYMBOL: char = '#';
+/// No version allows more than 127 characters plus the termination symbol.
+/// The exact limit is checked once the version of the input is known.
+pub(crate) const MAX_INPUT_LENGTH: usize = 128;
+
+/// The most segments that fit in [`MAX_INPUT_LENGTH`], e.g. `.0.0.0...`
+const MAX_SEGMENTS: usize = MAX_INPUT_LENGTH / 2;
+/// The length of a v3 base, the longest base of any version
+const MAX_BASE_LENGTH: usize = 23;
+
+/// The time and entropy an [`OverflowPolicy`] may need, if there are sources for them
+type Sources<'a> = Option<(&'a dyn Clock, &'a mut dyn EntropySource)>;
+
+/// The namespace [`CorrelationVector::new_from_key`] derives bases in, the v5 UUID of the URL of
+/// this crate's repository in the URL namespace
+pub const KEY_NAMESPACE: Uuid = Uuid::from_u128(0xa1ea43b6_ec68_5bcd_93a4_0a2696041e7f);
 
 /// The Correlation Vector struct
-#[derive(Eq, PartialEq, Debug)]
+///
+/// A correlation vector is stored inline in its serialized form, so creating, cloning, extending,
+/// incrementing and spinning it does not allocate, and [`CorrelationVector::as_str`] and
+/// [`Display`] only copy it.
+#[derive(Clone, Debug)]
 pub struct CorrelationVector {
-    base: String,
-    vector: Vec<u32>,
+    version: CorrelationVectorVersion,
+    /// The serialized form, updated by every operation
+    text: ArrayString<MAX_INPUT_LENGTH>,
     immutable: bool,
-    serialized_length: usize,
+    overflow_policy: OverflowPolicy,
+    spin_mode: SpinMode,
+    monotonic_spin: bool,
+    /// Boxed, as it is only set after [`OverflowPolicy::Rebase`] and would double the size of
+    /// every correlation vector otherwise
+    predecessor: Option<Box<ArrayString<MAX_INPUT_LENGTH>>>,
 }
 
 impl CorrelationVector {
     /// Creates a new CorrelationVector with a randomly generated UUID.
+    #[cfg(feature = "std")]
     pub fn new() -> CorrelationVector {
-        Self::new_from_uuid(Uuid::new_v4())
+        Self::new_with_entropy(&mut RandomEntropy)
+    }
+
+    /// Creates a new CorrelationVector with a UUID generated from the given entropy source.
+    pub fn new_with_entropy(entropy: &mut impl EntropySource) -> CorrelationVector {
+        Self::new_with_version_and_entropy(CorrelationVectorVersion::V2, entropy)
     }
 
     /// Create a new CorrelationVector from a given UUID.
     pub fn new_from_uuid(base: Uuid) -> CorrelationVector {
-        let mut base_string = base64::encode(base.as_bytes());
-        while let Some(c) = base_string.pop() {
-            if c != '=' {
-                base_string.push(c);
-                break;
+        Self::new_from_bytes(CorrelationVectorVersion::V2, base.as_bytes())
+    }
+
+    /// Create a new CorrelationVector whose base is derived from an external identifier, e.g. an
+    /// order ID, so that every correlation vector created for it shares a base.
+    ///
+    /// The base is the v5 UUID of `key` in [`KEY_NAMESPACE`], so the same key gives the same
+    /// base in every process and every release of this crate.
+    pub fn new_from_key(key: impl AsRef<[u8]>) -> CorrelationVector {
+        Self::new_from_key_in(KEY_NAMESPACE, key)
+    }
+
+    /// Create a new CorrelationVector whose base is the v5 UUID of `key` in `namespace`, so that
+    /// the same key gives different bases in different namespaces
+    pub fn new_from_key_in(namespace: Uuid, key: impl AsRef<[u8]>) -> CorrelationVector {
+        Self::new_from_uuid(Uuid::new_v5(&namespace, key.as_ref()))
+    }
+
+    /// Creates a new CorrelationVector of the given version with a randomly generated base.
+    #[cfg(feature = "std")]
+    pub fn new_with_version(version: CorrelationVectorVersion) -> CorrelationVector {
+        Self::new_with_version_and_entropy(version, &mut RandomEntropy)
+    }
+
+    /// Creates a new CorrelationVector of the given version with a base generated from the given
+    /// entropy source.
+    pub fn new_with_version_and_entropy(
+        version: CorrelationVectorVersion,
+        entropy: &mut impl EntropySource,
+    ) -> CorrelationVector {
+        Self::new_from_entropy(version, entropy)
+    }
+
+    fn new_from_entropy(
+        version: CorrelationVectorVersion,
+        entropy: &mut dyn EntropySource,
+    ) -> CorrelationVector {
+        match version {
+            CorrelationVectorVersion::V1 => {
+                let mut bytes = [0; 12];
+                entropy.fill_bytes(&mut bytes);
+                Self::new_v1_from_bytes(bytes)
+            }
+            CorrelationVectorVersion::V2 | CorrelationVectorVersion::V3 => {
+                let mut bytes = [0; 16];
+                entropy.fill_bytes(&mut bytes);
+                let uuid = uuid::Builder::from_random_bytes(bytes).into_uuid();
+                Self::new_from_bytes(version, uuid.as_bytes())
             }
         }
-        base_string.shrink_to_fit();
-        let base_str_len = base_string.len();
-        CorrelationVector {
-            base: base_string,
-            vector: vec![0],
-            immutable: false,
-            serialized_length: base_str_len + 2,
-        }
+    }
+
+    /// Creates a new v1 CorrelationVector with a randomly generated base.
+    #[cfg(feature = "std")]
+    pub fn new_v1() -> CorrelationVector {
+        Self::new_with_version(CorrelationVectorVersion::V1)
+    }
+
+    /// Create a new v1 CorrelationVector whose base is the base64 encoding of the given bytes.
+    pub fn new_v1_from_bytes(base: [u8; 12]) -> CorrelationVector {
+        Self::new_from_bytes(CorrelationVectorVersion::V1, &base)
+    }
+
+    /// Creates a new v3 CorrelationVector with a randomly generated UUID.
+    #[cfg(feature = "std")]
+    pub fn new_v3() -> CorrelationVector {
+        Self::new_with_version(CorrelationVectorVersion::V3)
+    }
+
+    /// Create a new v3 CorrelationVector from a given UUID.
+    pub fn new_v3_from_uuid(base: Uuid) -> CorrelationVector {
+        Self::new_from_bytes(CorrelationVectorVersion::V3, base.as_bytes())
+    }
+
+    pub(crate) fn new_from_bytes(
+        version: CorrelationVectorVersion,
+        bytes: &[u8],
+    ) -> CorrelationVector {
+        let mut encoded = [0; MAX_BASE_LENGTH];
+        let start = if version == CorrelationVectorVersion::V3 {
+            encoded[0] = V3_VERSION_SYMBOL as u8;
+            1
+        } else {
+            0
+        };
+        let length =
+            base64::encode_config_slice(bytes, base64::STANDARD_NO_PAD, &mut encoded[start..]);
+        let base = core::str::from_utf8(&encoded[..start + length]).expect("Base64 is ASCII");
+        Self::from_parts(version, base, None, [0], false)
     }
 
     /// Create a new CorrelationVector struct from a string representation of a CorrelationVector.
+    ///
+    /// This parser is lenient: it accepts any base and anything [`u32`]'s `FromStr` accepts as a
+    /// segment. A 16 character base is read as v1, a 23 character base as v3 and anything else
+    /// as v2.
+    /// Use [`CorrelationVector::parse_strict`] to reject input the specification forbids.
     pub fn parse(input: &str) -> Result<CorrelationVector, CorrelationVectorParseError> {
-        if input.len() > 128 || (input.len() == 128 && !input.ends_with(TERMINATION_SYMBOL)) {
+        if input.is_empty() {
+            return Err(CorrelationVectorParseError::Empty);
+        }
+        if input.len() > MAX_INPUT_LENGTH {
             return Err(CorrelationVectorParseError::StringTooLongError);
         }
 
-        let mut input = input;
-        if input.ends_with(TERMINATION_SYMBOL) {
-            input = input.trim_end_matches(TERMINATION_SYMBOL);
+        let immutable = input.ends_with(TERMINATION_SYMBOL);
+        let input = input.trim_end_matches(TERMINATION_SYMBOL);
+
+        let (base, vector) = input
+            .split_once('.')
+            .ok_or(CorrelationVectorParseError::MissingVector)?;
+        let (base, reset) = match base.split_once(RESET_SYMBOL) {
+            Some((base, reset)) => (base, Some(u64::from_str_radix(reset, 16)?)),
+            None => (base, None),
+        };
+        let version = CorrelationVectorVersion::from_base_length(base.len())
+            .unwrap_or(CorrelationVectorVersion::V2);
+        // segments only get shorter when written back, e.g. 007 becomes 7, so they fit
+        let mut cv = Self::from_parts(version, base, reset, None, false);
+        for segment in vector.split('.') {
+            cv.push_segment(segment.parse::<u32>()?);
+        }
+        if immutable {
+            cv.terminate();
         }
+        if cv.serialized_len() > cv.version.max_length() {
+            return Err(CorrelationVectorParseError::StringTooLongError);
+        }
+        Ok(cv)
+    }
 
-        let parts = input
+    /// Create a new CorrelationVector struct from a string representation of a CorrelationVector,
+    /// validating it against the specification.
+    ///
+    /// The base must be 16 (v1) or 22 (v2) characters of the base64 alphabet, or the version
+    /// character `A` followed by 22 such characters (v3), and must decode without leftover bits,
+    /// like the base64 encoding of a UUID. Only a v3 base may be followed by a
+    /// reset marker: `#` and a lowercase hexadecimal value without leading zeros. Every segment
+    /// must be a canonical decimal [`u32`] (no sign, no leading zeros), the input must fit in the
+    /// version's length limit and the only `!` allowed is a single termination marker at the end
+    /// of a v2 or v3 correlation vector.
+    ///
+    /// Use [`CorrelationVectorRef::parse`] to validate input without copying it.
+    pub fn parse_strict(input: &str) -> Result<CorrelationVector, CorrelationVectorParseError> {
+        CorrelationVectorRef::parse(input).map(CorrelationVector::from)
+    }
+
+    /// The version of the specification this CorrelationVector follows
+    pub fn version(&self) -> CorrelationVectorVersion {
+        self.version
+    }
+
+    /// Assemble a correlation vector from parts that fit in [`MAX_INPUT_LENGTH`]
+    pub(crate) fn from_parts(
+        version: CorrelationVectorVersion,
+        base: &str,
+        reset: Option<u64>,
+        segments: impl IntoIterator<Item = u32>,
+        immutable: bool,
+    ) -> CorrelationVector {
+        let mut cv = CorrelationVector {
+            version,
+            text: ArrayString::from(base).expect("Base is longer than a correlation vector"),
+            immutable: false,
+            overflow_policy: OverflowPolicy::default_for_version(version),
+            spin_mode: SpinMode::default(),
+            monotonic_spin: false,
+            predecessor: None,
+        };
+        if let Some(reset) = reset {
+            cv.push_reset(reset);
+        }
+        for segment in segments {
+            cv.push_segment(segment);
+        }
+        if immutable {
+            cv.terminate();
+        }
+        cv
+    }
+
+    /// The string representation of the correlation vector, as written by [`Display`]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The base, including the version character of a v3 base but not the reset marker
+    pub fn base(&self) -> &str {
+        let end = self
+            .text
+            .find([RESET_SYMBOL, '.'])
+            .unwrap_or_else(|| self.text.len());
+        &self.text[..end]
+    }
+
+    /// The value of the reset marker of a v3 correlation vector, see
+    /// [`CorrelationVector::reset`]
+    pub fn reset_marker(&self) -> Option<u64> {
+        let marker = self.text[self.base().len()..].strip_prefix(RESET_SYMBOL)?;
+        let end = marker.find('.').unwrap_or(marker.len());
+        let reset =
+            u64::from_str_radix(&marker[..end], 16).expect("Reset markers are written as u64");
+        Some(reset)
+    }
+
+    /// The UUID encoded in the base of a v2 or v3 correlation vector. Returns `None` for v1
+    /// bases and for bases that do not decode to exactly 16 bytes.
+    pub fn base_uuid(&self) -> Option<Uuid> {
+        let encoded = match self.version {
+            CorrelationVectorVersion::V1 => return None,
+            CorrelationVectorVersion::V2 => self.base(),
+            CorrelationVectorVersion::V3 => self.base().strip_prefix(V3_VERSION_SYMBOL)?,
+        };
+        // lenient parsing reads bases of any other length as v2
+        if encoded.len() != 22 {
+            return None;
+        }
+        // base64 decodes in blocks of 3 bytes, so 22 characters need room for 18
+        let mut bytes = [0; 18];
+        let length =
+            base64::decode_config_slice(encoded, base64::STANDARD_NO_PAD, &mut bytes).ok()?;
+        let bytes = <[u8; 16]>::try_from(&bytes[..length]).ok()?;
+        Some(Uuid::from_bytes(bytes))
+    }
+
+    /// The segments of the vector clock
+    pub fn segments(&self) -> impl Iterator<Item = u32> + '_ {
+        let text = self.text.trim_end_matches(TERMINATION_SYMBOL);
+        let start = text.find('.').map_or(text.len(), |dot| dot + 1);
+        text[start..]
             .split('.')
-            .collect::<Vec<&str>>();
-        match *parts.as_slice() {
-            [base, _first, ..] => Ok(CorrelationVector {
-                base: base.to_string(),
-                vector: parts[1..]
-                    .iter()
-                    .map(|s| s.parse::<u32>())
-                    .collect::<Result<Vec<u32>, ParseIntError>>()?,
-                immutable: input.ends_with(TERMINATION_SYMBOL),
-                serialized_length: input.len(),
-            }),
-            [_] => Err(CorrelationVectorParseError::MissingVector),
-            [] => Err(CorrelationVectorParseError::Empty),
+            .map(|segment| segment.parse().expect("Segments are written as u32"))
+    }
+
+    /// The number of segments in the vector clock
+    pub fn depth(&self) -> usize {
+        self.text.bytes().filter(|&b| b == b'.').count()
+    }
+
+    /// The length of the string representation without the termination symbol, which is what
+    /// counts against the length limit of the version
+    pub fn serialized_len(&self) -> usize {
+        self.text.len() - usize::from(self.immutable)
+    }
+
+    /// How many more characters fit before the length limit of the version is reached
+    pub fn remaining_capacity(&self) -> usize {
+        self.version
+            .max_length()
+            .saturating_sub(self.serialized_len())
+    }
+
+    fn terminate(&mut self) {
+        if !self.immutable {
+            self.text.push_str(TERMINATION_SYMBOL);
+            self.immutable = true;
         }
     }
 
+    fn push_reset(&mut self, reset: u64) {
+        write!(self.text, "{}{:x}", RESET_SYMBOL, reset)
+            .expect("Length is checked before the correlation vector grows");
+    }
+
+    fn push_segment(&mut self, segment: u32) {
+        write!(self.text, ".{}", segment)
+            .expect("Length is checked before the correlation vector grows");
+    }
+
+    /// The latest clock in the vector clock
+    pub(crate) fn last_segment(&self) -> u32 {
+        let text = self.text.trim_end_matches(TERMINATION_SYMBOL);
+        let last_dot = text
+            .rfind('.')
+            .expect("Vector clock has at least one segment");
+        text[last_dot + 1..]
+            .parse()
+            .expect("Segments are written as u32")
+    }
+
+    /// Replace the latest clock in the vector clock, which must not make it exceed the length
+    /// limit
+    pub(crate) fn set_last_segment(&mut self, segment: u32) {
+        let last_dot = self
+            .text
+            .rfind('.')
+            .expect("Vector clock has at least one segment");
+        self.text.truncate(last_dot);
+        self.push_segment(segment);
+    }
+
+    /// Whether the correlation vector is terminated and can no longer change
+    pub fn is_immutable(&self) -> bool {
+        self.immutable
+    }
+
+    /// Use the given policy when an operation would exceed the length limit.
+    /// Defaults to [`OverflowPolicy::default_for_version`].
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> CorrelationVector {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// Change the policy used when an operation would exceed the length limit
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow_policy = policy;
+    }
+
+    /// The policy used when an operation would exceed the length limit
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// Spin with the timestamp and segment order of the given mode.
+    /// Defaults to [`SpinMode::Unix`].
+    pub fn with_spin_mode(mut self, mode: SpinMode) -> CorrelationVector {
+        self.spin_mode = mode;
+        self
+    }
+
+    /// Change the timestamp and segment order used by [`CorrelationVector::spin`]
+    pub fn set_spin_mode(&mut self, mode: SpinMode) {
+        self.spin_mode = mode;
+    }
+
+    /// The timestamp and segment order used by [`CorrelationVector::spin`] and
+    /// [`CorrelationVector::decode_spin`]
+    pub fn spin_mode(&self) -> SpinMode {
+        self.spin_mode
+    }
+
+    /// Make every value spun by this correlation vector sort strictly after the previous value
+    /// spun in this process with the same bit layout and mode, bumping it on collision.
+    ///
+    /// The last spun values are shared by all correlation vectors of the process, so spinning
+    /// takes a lock while this is enabled. Defaults to `false`.
+    pub fn with_monotonic_spin(mut self, monotonic: bool) -> CorrelationVector {
+        self.monotonic_spin = monotonic;
+        self
+    }
+
+    /// Change whether spun values strictly increase within the process, see
+    /// [`CorrelationVector::with_monotonic_spin`]
+    pub fn set_monotonic_spin(&mut self, monotonic: bool) {
+        self.monotonic_spin = monotonic;
+    }
+
+    /// Whether spun values strictly increase within the process, see
+    /// [`CorrelationVector::with_monotonic_spin`]
+    pub fn monotonic_spin(&self) -> bool {
+        self.monotonic_spin
+    }
+
+    /// Take the overflow policy and spin settings of `other`, for a correlation vector derived
+    /// from it
+    pub(crate) fn with_settings_of(self, other: &CorrelationVector) -> CorrelationVector {
+        self.with_overflow_policy(other.overflow_policy)
+            .with_spin_mode(other.spin_mode)
+            .with_monotonic_spin(other.monotonic_spin)
+    }
+
+    /// The correlation vector this one replaced when [`OverflowPolicy::Rebase`] started over
+    /// with a new base
+    pub fn predecessor(&self) -> Option<&str> {
+        self.predecessor
+            .as_deref()
+            .map(|predecessor| predecessor.as_str())
+    }
+
     /// Append a new clock to the end of the vector clock
     pub fn extend(&mut self) {
+        let _ = self.try_extend();
+    }
+
+    /// Append a new clock to the end of the vector clock, reporting why nothing was appended.
+    ///
+    /// If the new clock does not fit, the [`OverflowPolicy`] is applied and
+    /// [`CorrelationVectorOperationError::Overflow`] is returned. Without the `std` feature
+    /// there is no clock or random number generator, so policies that need them terminate the
+    /// correlation vector instead. Use [`CorrelationVector::try_extend_with`] to provide them.
+    pub fn try_extend(&mut self) -> Result<(), CorrelationVectorOperationError> {
+        with_default_sources(|sources| self.try_extend_from(sources))
+    }
+
+    /// Extend the vector clock as [`CorrelationVector::extend`] does, reading the time and
+    /// entropy the [`OverflowPolicy`] may need from `clock` and `entropy`
+    pub fn extend_with(&mut self, clock: &impl Clock, entropy: &mut impl EntropySource) {
+        let _ = self.try_extend_with(clock, entropy);
+    }
+
+    /// Extend the vector clock as [`CorrelationVector::try_extend`] does, reading the time and
+    /// entropy the [`OverflowPolicy`] may need from `clock` and `entropy`
+    pub fn try_extend_with(
+        &mut self,
+        clock: &impl Clock,
+        entropy: &mut impl EntropySource,
+    ) -> Result<(), CorrelationVectorOperationError> {
+        self.try_extend_from(Some((clock, entropy)))
+    }
+
+    fn try_extend_from(&mut self, sources: Sources) -> Result<(), CorrelationVectorOperationError> {
         if self.immutable {
-            return;
+            return Err(CorrelationVectorOperationError::Terminated);
         }
-        let proposed_len = self.serialized_length + 2;
-        if proposed_len > 127 {
-            self.immutable = true;
-            return;
-        }
-        self.vector.push(0);
-        self.serialized_length = proposed_len; // .0
+        let proposed_len = self.serialized_len() + 2; // .0
+        self.check_length(proposed_len, sources)?;
+        self.push_segment(0);
+        Ok(())
     }
 
     /// Increment the latest clock in the vector clock
     pub fn increment(&mut self) {
+        let _ = self.try_increment();
+    }
+
+    /// Increment the latest clock in the vector clock, reporting why it was not incremented.
+    ///
+    /// If the incremented clock does not fit, the [`OverflowPolicy`] is applied and
+    /// [`CorrelationVectorOperationError::Overflow`] is returned. Without the `std` feature,
+    /// policies that need time or entropy terminate the correlation vector instead, as in
+    /// [`CorrelationVector::try_extend`].
+    pub fn try_increment(&mut self) -> Result<(), CorrelationVectorOperationError> {
+        with_default_sources(|sources| self.try_increment_from(sources))
+    }
+
+    /// Increment the vector clock as [`CorrelationVector::increment`] does, reading the time and
+    /// entropy the [`OverflowPolicy`] may need from `clock` and `entropy`
+    pub fn increment_with(&mut self, clock: &impl Clock, entropy: &mut impl EntropySource) {
+        let _ = self.try_increment_with(clock, entropy);
+    }
+
+    /// Increment the vector clock as [`CorrelationVector::try_increment`] does, reading the time
+    /// and entropy the [`OverflowPolicy`] may need from `clock` and `entropy`
+    pub fn try_increment_with(
+        &mut self,
+        clock: &impl Clock,
+        entropy: &mut impl EntropySource,
+    ) -> Result<(), CorrelationVectorOperationError> {
+        self.try_increment_from(Some((clock, entropy)))
+    }
+
+    fn try_increment_from(
+        &mut self,
+        sources: Sources,
+    ) -> Result<(), CorrelationVectorOperationError> {
         if self.immutable {
-            return;
+            return Err(CorrelationVectorOperationError::Terminated);
         }
-        let last_index = self.vector.len() - 1;
-        let prev = self.vector[last_index];
-
-        // if the last digit is 9, the serialized length will increase
-        if prev % 10 == 9 {
-            if self.serialized_length < 127 {
-                self.serialized_length += 1;
-            } else {
-                self.immutable = true;
+        let prev = self.last_segment();
+        let next = match prev.checked_add(1) {
+            Some(next) => next,
+            None => {
+                self.overflow(sources);
+                return Err(CorrelationVectorOperationError::ClockOverflow);
             }
-        }
+        };
 
-        if !self.immutable {
-            self.vector[last_index] = prev + 1;
-        }
+        // the serialized length grows when the clock gains a digit, e.g. 9 -> 10
+        let proposed_len =
+            self.serialized_len() - serialized_length_of(prev) + serialized_length_of(next);
+        self.check_length(proposed_len, sources)?;
+        self.set_last_segment(next);
+        Ok(())
     }
 
-    /// Transform the vector clock in a unique, monotonically increasing way. 
+    /// Transform the vector clock in a unique, monotonically increasing way.
     /// This is mostly used in situations where increment can not guaranatee uniqueness
-    pub fn spin(&mut self, params: SpinParams) {
+    ///
+    /// Takes [`SpinParams`](crate::SpinParams) or a custom [`SpinLayout`].
+    #[cfg(feature = "std")]
+    pub fn spin(&mut self, params: impl Into<SpinLayout>) {
+        let _ = self.try_spin(params);
+    }
+
+    /// Spin the vector clock as [`CorrelationVector::spin`] does, reading the time from `clock`
+    /// and the entropy from `entropy`
+    pub fn spin_with(
+        &mut self,
+        params: impl Into<SpinLayout>,
+        clock: &impl Clock,
+        entropy: &mut impl EntropySource,
+    ) {
+        let _ = self.try_spin_with(params, clock, entropy);
+    }
+
+    /// Spin the vector clock as [`CorrelationVector::spin`] does, reporting why it was not spun.
+    ///
+    /// If the spun value and the new clock do not fit, the [`OverflowPolicy`] is applied and
+    /// [`CorrelationVectorOperationError::Overflow`] is returned.
+    #[cfg(feature = "std")]
+    pub fn try_spin(
+        &mut self,
+        params: impl Into<SpinLayout>,
+    ) -> Result<(), CorrelationVectorOperationError> {
+        self.try_spin_with(params, &SystemClock, &mut RandomEntropy)
+    }
+
+    /// Spin the vector clock as [`CorrelationVector::try_spin`] does, reading the time from
+    /// `clock` and the entropy from `entropy`
+    pub fn try_spin_with(
+        &mut self,
+        params: impl Into<SpinLayout>,
+        clock: &impl Clock,
+        entropy: &mut impl EntropySource,
+    ) -> Result<(), CorrelationVectorOperationError> {
         if self.immutable {
-            return;
+            return Err(CorrelationVectorOperationError::Terminated);
         }
-        let entropy = generate_entropy(params.spin_entropy);
-        let ticks = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("Time is before the 0 epoch")
-            .as_nanos()
-            / 100;
+        let layout = params.into();
+        let ticks = ticks_since_epoch(clock) + epoch_offset_ticks(self.spin_mode);
+        let random = generate_entropy(u32::from(layout.entropy_bits()), entropy);
 
-        let mut value = u64::try_from(ticks >> ticks_to_drop(params.spin_counter_interval))
-            .expect("Number of ticks did not fit in u64");
+        // only the low bits of the timestamp are kept, and 64 bits of entropy leave no room
+        // for them
+        let mut value = (ticks >> layout.ticks_to_drop()) as u64;
+        value = value
+            .checked_shl(u32::from(layout.entropy_bits()))
+            .unwrap_or(0)
+            | random;
 
-        for byte in entropy {
-            value = (value << 8) | u64::from(byte);
+        value &= low_bits_mask(layout.value_bits());
+        if self.monotonic_spin {
+            value = next_monotonic(layout, self.spin_mode, value);
         }
 
-        let tick_bitmask_bits = tick_periodicity_bits(params);
-        let mask = if tick_bitmask_bits == 64 {
-            0
+        let low_bits = value as u32;
+        let mut extension = ArrayVec::<u32, 3>::new();
+        if layout.segment_count() > 1 {
+            let high_bits = (value >> 32) as u32;
+            match self.spin_mode {
+                SpinMode::Unix => extension.extend([low_bits, high_bits]),
+                SpinMode::Reference => extension.extend([high_bits, low_bits]),
+            }
         } else {
-            (1 << tick_bitmask_bits) - 1
-        };
+            extension.push(low_bits);
+        }
+        // the spun value is followed by a new clock
+        extension.push(0);
 
-        value &= mask;
+        let proposed_len = self.serialized_len()
+            + extension
+                .iter()
+                .map(|&segment| serialized_length_of(segment) + 1)
+                .sum::<usize>();
+        self.check_length(proposed_len, Some((clock, entropy)))?;
+        for segment in extension {
+            self.push_segment(segment);
+        }
+        Ok(())
+    }
 
-        let first_32_bits = value as u32;
-        let proposed_extension_len = serialized_length_of(first_32_bits) + 1;
-        if self.serialized_length + proposed_extension_len > 127 {
-            self.immutable = true;
+    /// Decode the spun value starting at segment `index` of the vector clock, assuming it was
+    /// produced with `params` in the [`SpinMode`] of this correlation vector. Returns `None` if
+    /// the vector clock has no such segments.
+    #[cfg(feature = "std")]
+    pub fn decode_spin(&self, index: usize, params: impl Into<SpinLayout>) -> Option<DecodedSpin> {
+        self.decode_spin_with(index, params, &SystemClock)
+    }
+
+    /// Decode a spun value as [`CorrelationVector::decode_spin`] does, resolving the time window
+    /// relative to `clock` instead of the current time
+    pub fn decode_spin_with(
+        &self,
+        index: usize,
+        params: impl Into<SpinLayout>,
+        clock: &impl Clock,
+    ) -> Option<DecodedSpin> {
+        let segments = self.segments().collect::<ArrayVec<u32, MAX_SEGMENTS>>();
+        decode_spin(
+            params.into(),
+            self.spin_mode,
+            &segments,
+            index,
+            ticks_since_epoch(clock),
+        )
+    }
+
+    /// Decode every spun value of a correlation vector that was only ever spun with `params`
+    /// after it was created, i.e. a root clock followed by a spun value and its clock per spin.
+    /// Use [`CorrelationVector::decode_spin`] for correlation vectors that were also extended.
+    #[cfg(feature = "std")]
+    pub fn decode_spins(&self, params: impl Into<SpinLayout>) -> Vec<DecodedSpin> {
+        self.decode_spins_with(params, &SystemClock)
+    }
+
+    /// Decode every spun value as [`CorrelationVector::decode_spins`] does, resolving the time
+    /// windows relative to `clock` instead of the current time
+    pub fn decode_spins_with(
+        &self,
+        params: impl Into<SpinLayout>,
+        clock: &impl Clock,
+    ) -> Vec<DecodedSpin> {
+        let layout = params.into();
+        let now_ticks = ticks_since_epoch(clock);
+        let stride = layout.segment_count() + 1;
+        let segments = self.segments().collect::<ArrayVec<u32, MAX_SEGMENTS>>();
+        (1..segments.len())
+            .step_by(stride)
+            .map_while(|index| decode_spin(layout, self.spin_mode, &segments, index, now_ticks))
+            .collect()
+    }
+
+    /// Replace the vector clock of a v3 CorrelationVector with a new clock under a fresh reset
+    /// marker, e.g. `AP9v1ltK2S7qTS77z0lWtKg.1.2.3` becomes `AP9v1ltK2S7qTS77z0lWtKg#186f9a1c44e3b2d7.0`.
+    /// The reset marker is derived from the current time and some entropy, so the result stays
+    /// unique without keeping the old clock. Terminated correlation vectors and other versions,
+    /// which have no reset operation, are left unchanged.
+    #[cfg(feature = "std")]
+    pub fn reset(&mut self) {
+        self.reset_with(&SystemClock, &mut RandomEntropy);
+    }
+
+    /// Reset the vector clock as [`CorrelationVector::reset`] does, reading the time from `clock`
+    /// and the entropy from `entropy`
+    pub fn reset_with(&mut self, clock: &impl Clock, entropy: &mut impl EntropySource) {
+        self.reset_from(clock, entropy);
+    }
+
+    fn reset_from(&mut self, clock: &dyn Clock, entropy: &mut dyn EntropySource) {
+        if self.version != CorrelationVectorVersion::V3 || self.immutable {
             return;
         }
-        self.serialized_length += proposed_extension_len;
-        self.vector.push(first_32_bits);
-        if tick_bitmask_bits > 32 {
-            let end_32_bits = (value >> 32) as u32;
-            let proposed_extension_len = serialized_length_of(end_32_bits) + 1;
-            if self.serialized_length + proposed_extension_len > 127 {
-                self.immutable = true;
-                return;
+        let ticks = u64::try_from(ticks_since_epoch(clock) & u128::from(u64::MAX >> 8))
+            .expect("Masked ticks did not fit in u64");
+        let mut random = [0];
+        entropy.fill_bytes(&mut random);
+        let reset = (ticks << 8) | u64::from(random[0]);
+
+        let base_length = self.base().len();
+        self.text.truncate(base_length);
+        self.push_reset(reset);
+        self.push_segment(0);
+    }
+
+    /// Apply the overflow policy if the proposed length exceeds the length limit
+    fn check_length(
+        &mut self,
+        proposed_len: usize,
+        sources: Sources,
+    ) -> Result<(), CorrelationVectorOperationError> {
+        let max_length = self.version.max_length();
+        if proposed_len <= max_length {
+            return Ok(());
+        }
+        let error = CorrelationVectorOperationError::Overflow {
+            required: proposed_len - self.serialized_len(),
+            remaining: self.remaining_capacity(),
+        };
+        self.overflow(sources);
+        Err(error)
+    }
+
+    /// Apply the overflow policy to an operation that would exceed the length limit.
+    /// Without sources, the policies that need them fall back to terminating.
+    fn overflow(&mut self, sources: Sources) {
+        match (self.overflow_policy, self.version, sources) {
+            (OverflowPolicy::Error, _, _) => {}
+            (OverflowPolicy::Reset, CorrelationVectorVersion::V3, Some((clock, entropy))) => {
+                self.reset_from(clock, entropy)
             }
-            self.vector.push(end_32_bits);
-            self.serialized_length += proposed_extension_len;
+            (OverflowPolicy::Rebase, _, Some((_, entropy))) => self.rebase(entropy),
+            (_, CorrelationVectorVersion::V1, _) => {}
+            _ => self.terminate(),
         }
+    }
 
-        if self.serialized_length + 2 > 127 {
-            self.immutable = true;
-            return;
+    /// Start over with a new random base, keeping the current correlation vector as predecessor
+    fn rebase(&mut self, entropy: &mut dyn EntropySource) {
+        let mut rebased = Self::new_from_entropy(self.version, entropy).with_settings_of(self);
+        rebased.predecessor = Some(Box::new(self.text));
+        *self = rebased;
+    }
+}
+
+/// Call `f` with the system clock and random number generator, if the `std` feature provides
+/// them
+fn with_default_sources<T>(f: impl FnOnce(Sources) -> T) -> T {
+    #[cfg(feature = "std")]
+    let sources = Some((
+        &SystemClock as &dyn Clock,
+        &mut RandomEntropy as &mut dyn EntropySource,
+    ));
+    #[cfg(not(feature = "std"))]
+    let sources = None;
+    f(sources)
+}
+
+/// The number of 100ns ticks since the UNIX epoch
+fn ticks_since_epoch(clock: &dyn Clock) -> u128 {
+    clock.now().as_nanos() / 100
+}
+
+pub(crate) fn validate_base(
+    base: &str,
+) -> Result<CorrelationVectorVersion, CorrelationVectorParseError> {
+    let version = CorrelationVectorVersion::from_base_length(base.len())
+        .ok_or(CorrelationVectorParseError::InvalidBaseLength { length: base.len() })?;
+    let encoded = if version == CorrelationVectorVersion::V3 {
+        match base.strip_prefix(V3_VERSION_SYMBOL) {
+            Some(encoded) => encoded,
+            None => {
+                return Err(CorrelationVectorParseError::InvalidBaseLength { length: base.len() })
+            }
         }
+    } else {
+        base
+    };
+    if let Some(character) = encoded.chars().find(|&c| !is_base64_char(c)) {
+        return Err(CorrelationVectorParseError::InvalidBaseCharacter { character });
+    }
+    // 22 characters hold 132 bits for 16 bytes, so the last character only carries 2 bits and
+    // must be one of A, Q, g or w. The 16 characters of a v1 base hold exactly 12 bytes.
+    if version != CorrelationVectorVersion::V1 {
+        let last = encoded
+            .chars()
+            .last()
+            .expect("Base length is checked above");
+        let padding = base64_value(last) % 16;
+        if padding != 0 {
+            return Err(CorrelationVectorParseError::InvalidBasePadding { character: last });
+        }
+    }
+    Ok(version)
+}
+
+pub(crate) fn parse_reset(
+    version: CorrelationVectorVersion,
+    reset: &str,
+) -> Result<u64, CorrelationVectorParseError> {
+    let canonical = version == CorrelationVectorVersion::V3
+        && !reset.is_empty()
+        && reset
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        && (reset == "0" || !reset.starts_with('0'));
+    if !canonical {
+        return Err(CorrelationVectorParseError::InvalidReset);
+    }
+    u64::from_str_radix(reset, 16).map_err(|_| CorrelationVectorParseError::InvalidReset)
+}
 
-        self.vector.push(0);
-        self.serialized_length += 2;
+fn is_base64_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '+' || c == '/'
+}
+
+/// The 6 bit value of a character of the base64 alphabet
+fn base64_value(c: char) -> u8 {
+    match c {
+        'A'..='Z' => c as u8 - b'A',
+        'a'..='z' => c as u8 - b'a' + 26,
+        '0'..='9' => c as u8 - b'0' + 52,
+        '+' => 62,
+        _ => 63,
+    }
+}
+
+pub(crate) fn parse_segment(
+    index: usize,
+    segment: &str,
+) -> Result<u32, CorrelationVectorParseError> {
+    let canonical = !segment.is_empty()
+        && segment.bytes().all(|b| b.is_ascii_digit())
+        && (segment == "0" || !segment.starts_with('0'));
+    if !canonical {
+        return Err(CorrelationVectorParseError::InvalidSegment { index });
     }
+    segment
+        .parse::<u32>()
+        .map_err(|_| CorrelationVectorParseError::InvalidSegment { index })
 }
 
-fn serialized_length_of(input: u32) -> usize {
+pub(crate) fn serialized_length_of(input: u32) -> usize {
     let mut length = 1;
     let mut input = input;
-    while input > 10 {
+    while input >= 10 {
         length += 1;
         input /= 10;
     }
     length
 }
 
+#[cfg(feature = "std")]
 impl Default for CorrelationVector {
     fn default() -> Self {
         Self::new()
@@ -188,30 +863,21 @@ impl Default for CorrelationVector {
 }
 
 impl Display for CorrelationVector {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-        let vector_string: String = self
-            .vector
-            .iter()
-            .map(|i| i.to_string())
-            .collect::<Vec<String>>()
-            .join(".");
-        write!(
-            f,
-            "{}.{}{}",
-            self.base,
-            vector_string,
-            if self.immutable { "!" } else { "" }
-        )
+    fn fmt(&self, f: &mut Formatter) -> Result<(), core::fmt::Error> {
+        f.write_str(self.as_str())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::{format, string::ToString, vec};
+    use core::time::Duration;
 
-    use crate::spinparams::{SpinCounterInterval, SpinCounterPeriodicity, SpinEntropy};
+    use crate::spinparams::{SpinCounterInterval, SpinCounterPeriodicity, SpinEntropy, SpinParams};
 
     use super::*;
 
+    #[cfg(feature = "std")]
     #[test]
     fn generate_cv() {
         let cv = CorrelationVector::new();
@@ -219,6 +885,7 @@ mod tests {
         assert_eq!(cv_string.split('.').count(), 2);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn parse_cv_works() {
         let cv = CorrelationVector::new();
@@ -227,6 +894,7 @@ mod tests {
         assert_eq!(cv, cv_parsed.expect("Failed to parse cV"));
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn increment_cv() {
         let mut cv = CorrelationVector::new();
@@ -235,6 +903,7 @@ mod tests {
         assert!(cv_string.ends_with('1'));
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn extend_cv() {
         let mut cv = CorrelationVector::new();
@@ -243,6 +912,7 @@ mod tests {
         assert_eq!(cv_string.split('.').count(), 3);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn spin_cv() {
         let mut cv = CorrelationVector::new();
@@ -256,6 +926,7 @@ mod tests {
         assert!(cv_string.ends_with('0'));
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn extend_stops_when_oversize() {
         let mut cv = CorrelationVector::new();
@@ -267,6 +938,7 @@ mod tests {
         assert!(cv_string.ends_with(TERMINATION_SYMBOL));
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn spin_stops_when_oversize() {
         let mut cv = CorrelationVector::new();
@@ -285,9 +957,12 @@ mod tests {
 
     #[test]
     fn increment_stops_when_oversize() {
+        // The 127 character input only overflows if the increment adds a digit. This used to
+        // end in .12344459, which only overflowed because the length was assumed to grow for
+        // every clock ending in 9, although 12344459 -> 12344460 keeps its length.
         let mut cv = CorrelationVector::parse(
             "P9v1ltK2S7qTS77z0lWtKg.0.386394219.0.386383989.0.386344389.0.386372594.0.386391233.0.386360320.0\
-            .386386342.0.386341105.12344459"
+            .386386342.0.386341105.99999999"
         ).unwrap();
 
         cv.increment();
@@ -297,9 +972,832 @@ mod tests {
         assert!(cv_string.ends_with(TERMINATION_SYMBOL));
     }
 
+    #[test]
+    fn increment_without_added_digit_fits() {
+        let input = "P9v1ltK2S7qTS77z0lWtKg.0.386394219.0.386383989.0.386344389.0.386372594.0.386391233.0.386360320.0\
+            .386386342.0.386341105.12344459";
+        let mut cv = CorrelationVector::parse(input).unwrap();
+        assert_eq!(cv.serialized_len(), 127);
+
+        cv.increment();
+
+        assert_eq!(cv.to_string(), input.replace("12344459", "12344460"));
+        assert!(!cv.is_immutable());
+    }
+
     #[test]
     fn parse_terminated() {
         let res = CorrelationVector::parse("base.0!");
         assert!(res.is_ok(), "{:?}", res);
     }
+
+    #[test]
+    fn parse_terminated_is_immutable() {
+        let mut cv = CorrelationVector::parse("base.0!").unwrap();
+        cv.extend();
+        assert_eq!(cv.to_string(), "base.0!");
+    }
+
+    #[test]
+    fn new_from_uuid_encodes_base() {
+        let cv = CorrelationVector::new_from_uuid(Uuid::nil());
+        assert_eq!(cv.to_string(), "AAAAAAAAAAAAAAAAAAAAAA.0");
+        let cv = CorrelationVector::new_v3_from_uuid(Uuid::from_bytes([0xff; 16]));
+        assert_eq!(cv.to_string(), "A/////////////////////w.0");
+        let cv = CorrelationVector::new_v1_from_bytes([0xff; 12]);
+        assert_eq!(cv.to_string(), "////////////////.0");
+    }
+
+    #[test]
+    fn new_from_key_is_stable() {
+        assert_eq!(
+            KEY_NAMESPACE,
+            Uuid::new_v5(
+                &Uuid::NAMESPACE_URL,
+                b"https://github.com/TransientError/CorrelationVector-rust"
+            )
+        );
+
+        let cv = CorrelationVector::new_from_key("order-42");
+        assert_eq!(cv.to_string(), "6Q/PQX1VV8ezhYg9XDD5Bg.0");
+        assert_eq!(cv, CorrelationVector::new_from_key(b"order-42"));
+        assert_eq!(
+            CorrelationVector::parse_strict(cv.as_str()).unwrap(),
+            cv.clone()
+        );
+        assert_eq!(CorrelationVector::parse(cv.as_str()).unwrap(), cv);
+        assert_ne!(
+            cv.base(),
+            CorrelationVector::new_from_key("order-43").base()
+        );
+
+        let namespaced = CorrelationVector::new_from_key_in(Uuid::NAMESPACE_OID, "order-42");
+        assert_ne!(namespaced.base(), cv.base());
+        assert_eq!(
+            namespaced.base_uuid(),
+            Some(Uuid::new_v5(&Uuid::NAMESPACE_OID, b"order-42"))
+        );
+        assert_eq!(namespaced.base().len(), 22);
+    }
+
+    #[test]
+    fn as_str_tracks_operations() {
+        let mut cv = CorrelationVector::parse("P9v1ltK2S7qTS77z0lWtKg.9").unwrap();
+        assert_eq!(cv.as_str(), "P9v1ltK2S7qTS77z0lWtKg.9");
+        cv.increment();
+        assert_eq!(cv.as_str(), "P9v1ltK2S7qTS77z0lWtKg.10");
+        cv.extend();
+        assert_eq!(cv.as_str(), "P9v1ltK2S7qTS77z0lWtKg.10.0");
+        cv.spin_with(SpinParams::default(), &fixed_clock, &mut CountingEntropy(0));
+        assert_eq!(cv.as_str(), cv.to_string());
+        assert_eq!(cv.as_str().split('.').count(), 5);
+
+        let input = "P9v1ltK2S7qTS77z0lWtKg.2!";
+        assert_eq!(CorrelationVector::parse(input).unwrap().as_str(), input);
+        assert_eq!(
+            CorrelationVector::parse_strict(input).unwrap().as_str(),
+            input
+        );
+        assert_eq!(
+            CorrelationVector::parse(input)
+                .unwrap()
+                .segments()
+                .collect::<Vec<u32>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn terminating_overflow_appends_termination_symbol() {
+        let input = format!(
+            "P9v1ltK2S7qTS77z0lWtKg.{}.99999",
+            ["2147483647"; 9].join(".")
+        );
+        let mut cv = CorrelationVector::parse(&input).unwrap();
+        cv.extend();
+        assert_eq!(cv.as_str(), format!("{}!", input));
+        assert_eq!(cv.serialized_len(), input.len());
+    }
+
+    #[test]
+    fn accessors() {
+        let uuid = Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+        let mut cv = CorrelationVector::new_from_uuid(uuid);
+        cv.extend();
+        cv.increment();
+        assert_eq!(cv.base(), cv.as_str().split('.').next().unwrap());
+        assert_eq!(cv.base_uuid(), Some(uuid));
+        assert_eq!(cv.segments().collect::<Vec<u32>>(), vec![0, 1]);
+        assert_eq!(cv.depth(), 2);
+        assert!(!cv.is_immutable());
+        assert_eq!(cv.serialized_len(), 26);
+        assert_eq!(cv.remaining_capacity(), 127 - 26);
+
+        let cv = CorrelationVector::new_v3_from_uuid(uuid);
+        assert_eq!(cv.base_uuid(), Some(uuid));
+        assert_eq!(cv.base().len(), 23);
+
+        let cv = CorrelationVector::parse_strict("AP9v1ltK2S7qTS77z0lWtKg#1f.3.10!").unwrap();
+        assert_eq!(cv.base(), "AP9v1ltK2S7qTS77z0lWtKg");
+        assert_eq!(cv.reset_marker(), Some(0x1f));
+        assert_eq!(cv.segments().collect::<Vec<u32>>(), vec![3, 10]);
+        assert_eq!(cv.depth(), 2);
+        assert!(cv.is_immutable());
+        assert_eq!(cv.serialized_len(), "AP9v1ltK2S7qTS77z0lWtKg#1f.3.10".len());
+
+        let cv = CorrelationVector::new_v1_from_bytes([7; 12]);
+        assert_eq!(cv.base_uuid(), None);
+        let cv = CorrelationVector::parse("base.1").unwrap();
+        assert_eq!(cv.base_uuid(), None);
+        assert_eq!(cv.remaining_capacity(), 127 - 6);
+        let cv = CorrelationVector::parse("P9v1ltK2S7qTS77z0lWtKgw0Ag.9").unwrap();
+        assert_eq!(cv.base_uuid(), None);
+    }
+
+    #[test]
+    fn size_is_bounded_by_max_length() {
+        // the serialized form, its length and a few bytes of settings
+        assert!(core::mem::size_of::<CorrelationVector>() <= MAX_INPUT_LENGTH + 24);
+    }
+
+    #[test]
+    fn clone_is_independent() {
+        let mut cv = CorrelationVector::parse("P9v1ltK2S7qTS77z0lWtKg.1").unwrap();
+        let clone = cv.clone();
+        cv.extend();
+        assert_eq!(clone.to_string(), "P9v1ltK2S7qTS77z0lWtKg.1");
+        assert_eq!(cv.to_string(), "P9v1ltK2S7qTS77z0lWtKg.1.0");
+    }
+
+    #[test]
+    fn parse_computes_serialized_len() {
+        let cv = CorrelationVector::parse("base.10.0!").unwrap();
+        assert_eq!(cv.serialized_len(), "base.10.0".len());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn parse_strict_accepts_generated_cv() {
+        let cv = CorrelationVector::new();
+        let cv_parsed = CorrelationVector::parse_strict(&cv.to_string());
+        assert_eq!(cv, cv_parsed.expect("Failed to parse cV"));
+
+        let cv = CorrelationVector::parse_strict("P9v1ltK2S7qTS77z0lWtKg.0.10!").unwrap();
+        assert_eq!(cv.to_string(), "P9v1ltK2S7qTS77z0lWtKg.0.10!");
+        assert!(cv.immutable);
+    }
+
+    #[test]
+    fn parse_strict_rejects_invalid_input() {
+        assert!(matches!(
+            CorrelationVector::parse_strict(""),
+            Err(CorrelationVectorParseError::Empty)
+        ));
+        assert!(matches!(
+            CorrelationVector::parse_strict("base.0"),
+            Err(CorrelationVectorParseError::InvalidBaseLength { length: 4 })
+        ));
+        assert!(matches!(
+            CorrelationVector::parse_strict("P9v1ltK2S7qTS77z0lWt-g.0"),
+            Err(CorrelationVectorParseError::InvalidBaseCharacter { character: '-' })
+        ));
+        assert!(matches!(
+            CorrelationVector::parse_strict("AAAAAAAAAAAAAAAAAAAAAB.0"),
+            Err(CorrelationVectorParseError::InvalidBasePadding { character: 'B' })
+        ));
+        assert!(matches!(
+            CorrelationVector::parse_strict("AP9v1ltK2S7qTS77z0lWtK/.0"),
+            Err(CorrelationVectorParseError::InvalidBasePadding { character: '/' })
+        ));
+        assert!(matches!(
+            CorrelationVector::parse_strict("P9v1ltK2S7qTS77z0lWtKg"),
+            Err(CorrelationVectorParseError::MissingVector)
+        ));
+        for (input, bad_index) in [
+            ("P9v1ltK2S7qTS77z0lWtKg.+1", 0),
+            ("P9v1ltK2S7qTS77z0lWtKg.0.007", 1),
+            ("P9v1ltK2S7qTS77z0lWtKg.0.", 1),
+            ("P9v1ltK2S7qTS77z0lWtKg.4294967296", 0),
+            ("P9v1ltK2S7qTS77z0lWtKg.0!!", 0),
+        ] {
+            match CorrelationVector::parse_strict(input) {
+                Err(CorrelationVectorParseError::InvalidSegment { index }) => {
+                    assert_eq!(index, bad_index, "{}", input)
+                }
+                other => panic!("{} parsed as {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn increment_tracks_serialized_len() {
+        let mut cv = CorrelationVector::parse("P9v1ltK2S7qTS77z0lWtKg.18").unwrap();
+        cv.increment();
+        assert_eq!(cv.serialized_len(), "P9v1ltK2S7qTS77z0lWtKg.19".len());
+        cv.increment();
+        assert_eq!(cv.serialized_len(), "P9v1ltK2S7qTS77z0lWtKg.20".len());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn generate_v1_cv() {
+        let cv = CorrelationVector::new_v1_from_bytes([0; 12]);
+        assert_eq!(cv.to_string(), "AAAAAAAAAAAAAAAA.0");
+        assert_eq!(cv.version(), CorrelationVectorVersion::V1);
+
+        let cv_string = CorrelationVector::new_v1().to_string();
+        assert_eq!(cv_string.len(), 18);
+    }
+
+    #[test]
+    fn parse_detects_version() {
+        let v1 = CorrelationVector::parse_strict("tul4NUsfs9Cl7mOf.1.2").unwrap();
+        assert_eq!(v1.version, CorrelationVectorVersion::V1);
+        let v2 = CorrelationVector::parse_strict("P9v1ltK2S7qTS77z0lWtKg.1.2").unwrap();
+        assert_eq!(v2.version, CorrelationVectorVersion::V2);
+        assert_eq!(
+            CorrelationVector::parse("tul4NUsfs9Cl7mOf.1")
+                .unwrap()
+                .version,
+            CorrelationVectorVersion::V1
+        );
+    }
+
+    #[test]
+    fn parse_strict_enforces_v1_rules() {
+        assert!(matches!(
+            CorrelationVector::parse_strict("tul4NUsfs9Cl7mOf.1!"),
+            Err(CorrelationVectorParseError::UnexpectedTermination)
+        ));
+        let too_long = format!("tul4NUsfs9Cl7mOf{}", ".1".repeat(24));
+        assert_eq!(too_long.len(), 64);
+        assert!(matches!(
+            CorrelationVector::parse_strict(&too_long),
+            Err(CorrelationVectorParseError::StringTooLongError)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn v1_drops_operations_that_overflow() {
+        let mut cv = CorrelationVector::new_v1();
+        for _ in 0..64 {
+            cv.extend();
+        }
+        let cv_string = cv.to_string();
+        assert_eq!(cv_string.len(), 62);
+        assert!(!cv_string.ends_with(TERMINATION_SYMBOL));
+
+        cv.extend();
+        assert_eq!(cv.to_string(), cv_string);
+        for _ in 0..9 {
+            cv.increment();
+        }
+        assert_eq!(cv.to_string().len(), 62);
+        cv.increment();
+        assert_eq!(cv.to_string().len(), 63);
+        cv.increment();
+        assert_eq!(cv.to_string().len(), 63);
+        assert!(!cv.immutable);
+    }
+
+    #[test]
+    fn generate_v3_cv() {
+        let cv = CorrelationVector::new_v3_from_uuid(Uuid::nil());
+        assert_eq!(cv.to_string(), "AAAAAAAAAAAAAAAAAAAAAAA.0");
+        assert_eq!(cv.version, CorrelationVectorVersion::V3);
+        assert_eq!(
+            cv,
+            CorrelationVector::parse_strict("AAAAAAAAAAAAAAAAAAAAAAA.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_v3_reset() {
+        let input = "AP9v1ltK2S7qTS77z0lWtKg#186f9a1c44e3b2d7.0.1";
+        let cv = CorrelationVector::parse_strict(input).unwrap();
+        assert_eq!(cv.version, CorrelationVectorVersion::V3);
+        assert_eq!(cv.reset_marker(), Some(0x186f9a1c44e3b2d7));
+        assert_eq!(cv.serialized_len(), input.len());
+        assert_eq!(cv.to_string(), input);
+        assert_eq!(CorrelationVector::parse(input).unwrap(), cv);
+
+        for input in [
+            "P9v1ltK2S7qTS77z0lWtKg#1.0",
+            "AP9v1ltK2S7qTS77z0lWtKg#.0",
+            "AP9v1ltK2S7qTS77z0lWtKg#0a.0",
+            "AP9v1ltK2S7qTS77z0lWtKg#A.0",
+        ] {
+            assert!(
+                matches!(
+                    CorrelationVector::parse_strict(input),
+                    Err(CorrelationVectorParseError::InvalidReset)
+                ),
+                "{}",
+                input
+            );
+        }
+        assert!(matches!(
+            CorrelationVector::parse_strict("BP9v1ltK2S7qTS77z0lWtKg.0"),
+            Err(CorrelationVectorParseError::InvalidBaseLength { length: 23 })
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn reset_v3_cv() {
+        let mut cv = CorrelationVector::parse("AP9v1ltK2S7qTS77z0lWtKg.1.2.3").unwrap();
+        cv.reset();
+        let cv_string = cv.to_string();
+        assert!(cv_string.starts_with("AP9v1ltK2S7qTS77z0lWtKg#"));
+        assert!(cv_string.ends_with(".0"));
+        assert_eq!(cv.serialized_len(), cv_string.len());
+        assert_eq!(CorrelationVector::parse_strict(&cv_string).unwrap(), cv);
+
+        let mut v2 = CorrelationVector::parse("P9v1ltK2S7qTS77z0lWtKg.1.2").unwrap();
+        v2.reset();
+        assert_eq!(v2.to_string(), "P9v1ltK2S7qTS77z0lWtKg.1.2");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn reset_keeps_terminated_cv() {
+        let mut cv = CorrelationVector::parse("AP9v1ltK2S7qTS77z0lWtKg.1.2.3!").unwrap();
+        cv.reset();
+        assert_eq!(cv.to_string(), "AP9v1ltK2S7qTS77z0lWtKg.1.2.3!");
+        assert!(cv.is_immutable());
+        assert_eq!(cv.reset_marker(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn v3_resets_instead_of_terminating() {
+        let mut cv = CorrelationVector::new_v3();
+        for _ in 0..128 {
+            cv.extend();
+        }
+        let cv_string = cv.to_string();
+        assert!(cv_string.len() <= 127);
+        assert!(!cv_string.ends_with(TERMINATION_SYMBOL));
+        assert!(cv.reset_marker().is_some());
+        assert_eq!(cv.serialized_len(), cv_string.len());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn new_with_version() {
+        for version in [
+            CorrelationVectorVersion::V1,
+            CorrelationVectorVersion::V2,
+            CorrelationVectorVersion::V3,
+        ] {
+            let cv = CorrelationVector::new_with_version(version);
+            assert_eq!(cv.version(), version);
+            let cv_string = cv.to_string();
+            assert_eq!(cv_string.len(), version.base_length() + 2);
+            let cv_parsed = CorrelationVector::parse_strict(&cv_string).unwrap();
+            assert_eq!(cv_parsed.version(), version);
+        }
+    }
+
+    #[test]
+    fn try_operations_report_overflow() {
+        let mut cv =
+            CorrelationVector::parse(&format!("P9v1ltK2S7qTS77z0lWtKg{}", ".9".repeat(52)))
+                .unwrap();
+        assert_eq!(cv.serialized_len(), 126);
+        assert_eq!(
+            cv.try_extend(),
+            Err(CorrelationVectorOperationError::Overflow {
+                required: 2,
+                remaining: 1
+            })
+        );
+        assert!(cv.immutable);
+        assert_eq!(
+            cv.try_increment(),
+            Err(CorrelationVectorOperationError::Terminated)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn try_operations_succeed() {
+        let mut cv = CorrelationVector::new();
+        assert_eq!(cv.try_extend(), Ok(()));
+        assert_eq!(cv.try_increment(), Ok(()));
+        assert_eq!(
+            cv.try_spin(SpinParams {
+                spin_entropy: SpinEntropy::Two,
+                spin_counter_interval: SpinCounterInterval::Fine,
+                spin_counter_periodicity: SpinCounterPeriodicity::Short,
+            }),
+            Ok(())
+        );
+        assert_eq!(cv.serialized_len(), cv.to_string().len());
+    }
+
+    #[test]
+    fn try_increment_reports_clock_overflow() {
+        let mut cv = CorrelationVector::parse("tul4NUsfs9Cl7mOf.4294967295").unwrap();
+        assert_eq!(
+            cv.try_increment(),
+            Err(CorrelationVectorOperationError::ClockOverflow)
+        );
+        assert_eq!(cv.to_string(), "tul4NUsfs9Cl7mOf.4294967295");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn overflow_policy_defaults_to_version() {
+        assert_eq!(
+            CorrelationVector::new_v1().overflow_policy(),
+            OverflowPolicy::Error
+        );
+        assert_eq!(
+            CorrelationVector::new().overflow_policy(),
+            OverflowPolicy::Terminate
+        );
+        assert_eq!(
+            CorrelationVector::parse("AP9v1ltK2S7qTS77z0lWtKg.0")
+                .unwrap()
+                .overflow_policy(),
+            OverflowPolicy::Reset
+        );
+    }
+
+    #[test]
+    fn overflow_policy_error_keeps_cv_mutable() {
+        let input = format!("P9v1ltK2S7qTS77z0lWtKg{}", ".9".repeat(52));
+        let mut cv = CorrelationVector::parse(&input)
+            .unwrap()
+            .with_overflow_policy(OverflowPolicy::Error);
+        assert!(matches!(
+            cv.try_extend(),
+            Err(CorrelationVectorOperationError::Overflow { .. })
+        ));
+        assert_eq!(cv.to_string(), input);
+        assert!(!cv.immutable);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn overflow_policy_reset_terminates_v2() {
+        let mut cv = CorrelationVector::new().with_overflow_policy(OverflowPolicy::Reset);
+        for _ in 0..128 {
+            cv.extend();
+        }
+        assert!(cv.immutable);
+        assert_eq!(cv.reset_marker(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn overflow_policy_rebase_links_predecessor() {
+        let input = format!("P9v1ltK2S7qTS77z0lWtKg{}", ".9".repeat(52));
+        let mut cv = CorrelationVector::parse(&input)
+            .unwrap()
+            .with_overflow_policy(OverflowPolicy::Rebase);
+        assert!(cv.try_extend().is_err());
+        assert_eq!(cv.predecessor(), Some(input.as_str()));
+        assert_ne!(cv.base(), "P9v1ltK2S7qTS77z0lWtKg");
+        assert_eq!(cv.segments().collect::<Vec<u32>>(), vec![0]);
+        assert_eq!(cv.overflow_policy(), OverflowPolicy::Rebase);
+        assert_eq!(cv.version(), CorrelationVectorVersion::V2);
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[test]
+    fn overflow_terminates_without_sources() {
+        for (input, policy) in [
+            ("P9v1ltK2S7qTS77z0lWtKg", OverflowPolicy::Rebase),
+            ("AP9v1ltK2S7qTS77z0lWtKg", OverflowPolicy::Reset),
+        ] {
+            let input = format!("{}{}", input, ".9".repeat(52));
+            let mut cv = CorrelationVector::parse(&input)
+                .unwrap()
+                .with_overflow_policy(policy);
+            cv.extend();
+            assert!(cv.immutable);
+            assert_eq!(cv.predecessor(), None);
+            assert_eq!(cv.to_string(), format!("{}!", input));
+        }
+    }
+
+    #[test]
+    fn increment_with_uses_given_sources() {
+        let input = format!("AP9v1ltK2S7qTS77z0lWtKg{}.9", ".1".repeat(51));
+        let mut cv = CorrelationVector::parse(&input).unwrap();
+        assert!(cv
+            .try_increment_with(&fixed_clock, &mut CountingEntropy(7))
+            .is_err());
+        assert_eq!(cv.to_string(), "AP9v1ltK2S7qTS77z0lWtKg#38d7ea4c68000007.0");
+
+        let mut rebased = CorrelationVector::new_with_entropy(&mut CountingEntropy(0))
+            .with_overflow_policy(OverflowPolicy::Rebase);
+        for _ in 0..52 {
+            rebased.extend_with(&fixed_clock, &mut CountingEntropy(0));
+        }
+        let expected = CorrelationVector::new_with_entropy(&mut CountingEntropy(0));
+        assert_eq!(rebased.base(), expected.base());
+        assert!(rebased.predecessor().is_some());
+    }
+
+    struct CountingEntropy(u8);
+
+    impl EntropySource for CountingEntropy {
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest {
+                *byte = self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+        }
+    }
+
+    fn fixed_clock() -> Duration {
+        Duration::from_secs(1_600_000_000)
+    }
+
+    #[test]
+    fn new_with_entropy_is_deterministic() {
+        let cv = CorrelationVector::new_with_entropy(&mut CountingEntropy(0));
+        assert_eq!(cv.to_string(), "AAECAwQFRgeICQoLDA0ODw.0");
+
+        let cv = CorrelationVector::new_with_version_and_entropy(
+            CorrelationVectorVersion::V1,
+            &mut CountingEntropy(0),
+        );
+        assert_eq!(cv.to_string(), "AAECAwQFBgcICQoL.0");
+    }
+
+    #[test]
+    fn spin_with_is_deterministic() {
+        let mut cv = CorrelationVector::new_with_entropy(&mut CountingEntropy(0));
+        cv.spin_with(
+            SpinParams {
+                spin_entropy: SpinEntropy::Two,
+                spin_counter_interval: SpinCounterInterval::Fine,
+                spin_counter_periodicity: SpinCounterPeriodicity::Short,
+            },
+            &fixed_clock,
+            &mut CountingEntropy(1),
+        );
+        // (16000000000000000 >> 16) & 0xffff = 0x4c68, followed by the entropy bytes 0x01 0x02
+        assert_eq!(
+            cv.to_string(),
+            format!("AAECAwQFRgeICQoLDA0ODw.0.{}.0", 0x4c68_0102u32)
+        );
+    }
+
+    #[test]
+    fn reset_with_is_deterministic() {
+        let mut cv = CorrelationVector::new_v3_from_uuid(Uuid::nil());
+        cv.reset_with(&fixed_clock, &mut CountingEntropy(7));
+        assert_eq!(
+            cv.to_string(),
+            format!(
+                "AAAAAAAAAAAAAAAAAAAAAAA#{:x}.0",
+                (16_000_000_000_000_000u64 << 8) | 7
+            )
+        );
+    }
+
+    fn reference_clock() -> Duration {
+        // new DateTime(2020, 9, 13, 12, 26, 40, DateTimeKind.Utc).Ticks == 637355968000000000
+        Duration::from_secs(1_600_000_000)
+    }
+
+    #[test]
+    fn spin_reference_mode_matches_reference() {
+        // The values CorrelationVectorV2.Spin of the .NET implementation produces at
+        // DateTime.Ticks 637355968000000000 when Random.NextBytes returns 0x12 0x13 0x14 0x15,
+        // worked out from its C# code rather than from this implementation:
+        //   value = (ticks >> TicksBitsToDrop) << (8 * EntropyBytes), masked to TotalBits unless
+        //   TotalBits == 64, | entropy; written as (uint)(value >> 32) + "." + (uint)value when
+        //   TotalBits > 32, else as (uint)value
+        for (interval, periodicity, entropy, expected) in [
+            (
+                SpinCounterInterval::Coarse,
+                SpinCounterPeriodicity::Short,
+                SpinEntropy::Two,
+                "3762557459",
+            ),
+            (
+                SpinCounterInterval::Fine,
+                SpinCounterPeriodicity::Short,
+                SpinEntropy::None,
+                "17437",
+            ),
+            (
+                SpinCounterInterval::Fine,
+                SpinCounterPeriodicity::Medium,
+                SpinEntropy::Four,
+                "14697501.303240213",
+            ),
+            (
+                SpinCounterInterval::Coarse,
+                SpinCounterPeriodicity::Long,
+                SpinEntropy::Three,
+                "14178272.1142035220",
+            ),
+            (
+                SpinCounterInterval::Fine,
+                SpinCounterPeriodicity::Long,
+                SpinEntropy::Four,
+                "1474315293.303240213",
+            ),
+        ] {
+            let mut cv =
+                CorrelationVector::new_from_uuid(Uuid::nil()).with_spin_mode(SpinMode::Reference);
+            cv.spin_with(
+                SpinParams {
+                    spin_counter_interval: interval,
+                    spin_counter_periodicity: periodicity,
+                    spin_entropy: entropy,
+                },
+                &reference_clock,
+                &mut CountingEntropy(0x12),
+            );
+            assert_eq!(
+                cv.to_string(),
+                format!("AAAAAAAAAAAAAAAAAAAAAA.0.{}.0", expected)
+            );
+        }
+    }
+
+    #[test]
+    fn spin_settings_are_kept_by_derived_cvs() {
+        let cv = CorrelationVector::new_from_uuid(Uuid::nil());
+        assert_eq!(cv.spin_mode(), SpinMode::Unix);
+
+        let cv = cv.with_spin_mode(SpinMode::Reference);
+        assert_eq!(cv.child().unwrap().spin_mode(), SpinMode::Reference);
+        let mut rebased =
+            CorrelationVector::parse(&format!("P9v1ltK2S7qTS77z0lWtKg{}", ".9".repeat(52)))
+                .unwrap()
+                .with_overflow_policy(OverflowPolicy::Rebase)
+                .with_spin_mode(SpinMode::Reference);
+        rebased.extend_with(&fixed_clock, &mut CountingEntropy(0));
+        assert!(rebased.predecessor().is_some());
+        assert_eq!(rebased.spin_mode(), SpinMode::Reference);
+        assert!(!rebased.monotonic_spin());
+        assert!(cv
+            .with_monotonic_spin(true)
+            .child()
+            .unwrap()
+            .monotonic_spin());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn decode_spins_recovers_time_and_entropy() {
+        for spin_mode in [SpinMode::Unix, SpinMode::Reference] {
+            for spin_counter_periodicity in [
+                SpinCounterPeriodicity::Short,
+                SpinCounterPeriodicity::Medium,
+                SpinCounterPeriodicity::Long,
+            ] {
+                let params = SpinParams {
+                    spin_counter_interval: SpinCounterInterval::Fine,
+                    spin_counter_periodicity,
+                    spin_entropy: SpinEntropy::Four,
+                };
+                let mut cv = CorrelationVector::new().with_spin_mode(spin_mode);
+                cv.spin_with(params, &fixed_clock, &mut CountingEntropy(1));
+                cv.spin_with(params, &fixed_clock, &mut CountingEntropy(5));
+
+                let later = || fixed_clock() + Duration::from_secs(1);
+                let decoded = cv.decode_spins_with(params, &later);
+                assert_eq!(decoded.len(), 2, "{}", cv);
+                assert_eq!(decoded[0].entropy, 0x01020304);
+                assert_eq!(decoded[1].entropy, 0x05060708);
+                for spin in decoded {
+                    let window = spin.window.expect("Spin has counter bits");
+                    assert!(window.start <= fixed_clock(), "{:?}", window);
+                    assert!(fixed_clock() < window.end, "{:?}", window);
+                    // dropping 16 bits of 100ns ticks leaves a 6.5536ms window
+                    assert_eq!(
+                        window.end - window.start,
+                        Duration::from_micros(6553) + Duration::from_nanos(600)
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn decode_spin_without_counter() {
+        let params = SpinParams {
+            spin_counter_interval: SpinCounterInterval::Coarse,
+            spin_counter_periodicity: SpinCounterPeriodicity::None,
+            spin_entropy: SpinEntropy::One,
+        };
+        let cv = CorrelationVector::parse("P9v1ltK2S7qTS77z0lWtKg.1.0.200.0").unwrap();
+        let decoded = cv.decode_spin(2, params).unwrap();
+        assert_eq!(decoded.index, 2);
+        assert_eq!(decoded.window, None);
+        assert_eq!(decoded.entropy, 200);
+        assert_eq!(cv.decode_spin(5, params), None);
+    }
+
+    #[test]
+    fn decode_spins_of_terminated_cv() {
+        let params = SpinParams {
+            spin_counter_interval: SpinCounterInterval::Coarse,
+            spin_counter_periodicity: SpinCounterPeriodicity::None,
+            spin_entropy: SpinEntropy::One,
+        };
+        let cv = CorrelationVector::parse("P9v1ltK2S7qTS77z0lWtKg.1.200.0.100.0!").unwrap();
+        assert_eq!(cv.segments().collect::<Vec<u32>>(), vec![1, 200, 0, 100, 0]);
+        assert_eq!(cv.depth(), 5);
+        let decoded = cv.decode_spins_with(params, &fixed_clock);
+        assert_eq!(
+            decoded
+                .iter()
+                .map(|spin| spin.entropy)
+                .collect::<Vec<u64>>(),
+            vec![200, 100]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn spin_monotonic_strictly_increases() {
+        let params = SpinParams {
+            spin_counter_interval: SpinCounterInterval::Fine,
+            spin_counter_periodicity: SpinCounterPeriodicity::Short,
+            spin_entropy: SpinEntropy::None,
+        };
+        let threads = (0..4)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    (0..100)
+                        .map(|_| {
+                            let mut cv = CorrelationVector::new().with_monotonic_spin(true);
+                            cv.spin_with(params, &fixed_clock, &mut RandomEntropy);
+                            let spun = cv.segments().nth(1).unwrap();
+                            spun
+                        })
+                        .collect::<Vec<u32>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut all = Vec::new();
+        for thread in threads {
+            let values = thread.join().unwrap();
+            assert!(values.windows(2).all(|w| w[0] < w[1]), "{:?}", values);
+            all.extend(values);
+        }
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), 400);
+    }
+
+    #[test]
+    fn spin_custom_bit_widths() {
+        let params = SpinLayout::new(20, 20, 4).unwrap();
+        let mut cv =
+            CorrelationVector::new_from_uuid(Uuid::nil()).with_spin_mode(SpinMode::Reference);
+        cv.spin_with(params, &reference_clock, &mut CountingEntropy(0xab));
+        // (637355968000000000 >> 20) & 0xfffff = 0xe0441, followed by the low 4 bits of 0xab
+        assert_eq!(
+            cv.to_string(),
+            format!("AAAAAAAAAAAAAAAAAAAAAA.0.{}.0", 0xe0441b)
+        );
+
+        let params = SpinLayout::new(0, 0, 64).unwrap();
+        let mut cv =
+            CorrelationVector::new_from_uuid(Uuid::nil()).with_spin_mode(SpinMode::Reference);
+        cv.spin_with(params, &reference_clock, &mut CountingEntropy(1));
+        assert_eq!(
+            cv.to_string(),
+            format!("AAAAAAAAAAAAAAAAAAAAAA.0.{}.{}.0", 0x01020304, 0x05060708)
+        );
+    }
+
+    #[test]
+    fn spin_with_widest_bit_widths() {
+        // all 64 bits are entropy, leaving no window
+        let layout = SpinLayout::new(63, 0, 64).unwrap();
+        let mut cv = CorrelationVector::new_from_uuid(Uuid::nil());
+        cv.spin_with(layout, &reference_clock, &mut CountingEntropy(1));
+        assert_eq!(
+            cv.to_string(),
+            format!("AAAAAAAAAAAAAAAAAAAAAA.0.{}.{}.0", 0x05060708, 0x01020304)
+        );
+        let decoded = cv.decode_spin_with(1, layout, &reference_clock).unwrap();
+        assert_eq!(decoded.entropy, 0x01020304_05060708);
+        assert_eq!(decoded.window, None);
+
+        // all 64 bits are the counter, from a timestamp with 63 bits dropped
+        let layout = SpinLayout::new(63, 64, 0).unwrap();
+        let mut cv = CorrelationVector::new_from_uuid(Uuid::nil());
+        cv.spin_with(layout, &reference_clock, &mut CountingEntropy(1));
+        assert_eq!(cv.to_string(), "AAAAAAAAAAAAAAAAAAAAAA.0.0.0.0");
+        let decoded = cv.decode_spin_with(1, layout, &reference_clock).unwrap();
+        assert_eq!(decoded.entropy, 0);
+        assert!(decoded.window.is_some());
+    }
 }
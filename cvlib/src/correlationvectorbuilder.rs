@@ -0,0 +1,312 @@
+use alloc::{string::String, vec::Vec};
+
+use uuid::Uuid;
+
+use crate::{
+    correlationvector::{serialized_length_of, validate_base, CorrelationVector},
+    correlationvectorparsererror::CorrelationVectorParseError,
+    correlationvectorversion::CorrelationVectorVersion,
+    overflowpolicy::OverflowPolicy,
+};
+
+/// Builds a [`CorrelationVector`] from its parts, e.g. for tests and migrations
+/// ```rust
+/// use cvlib::{CorrelationVectorBuilder, CorrelationVectorVersion};
+/// use uuid::Uuid;
+///
+/// let cv = CorrelationVectorBuilder::new()
+///     .base_uuid(Uuid::nil())
+///     .version(CorrelationVectorVersion::V3)
+///     .segments([1, 2, 3])
+///     .immutable(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(cv.to_string(), "AAAAAAAAAAAAAAAAAAAAAAA.1.2.3!");
+/// ```
+/// The parts are checked against the rules [`CorrelationVector::parse_strict`] enforces,
+/// including the length limit of the version.
+#[derive(Debug, Clone, Default)]
+pub struct CorrelationVectorBuilder {
+    base: Option<Base>,
+    version: Option<CorrelationVectorVersion>,
+    reset: Option<u64>,
+    segments: Option<Vec<u32>>,
+    immutable: bool,
+    overflow_policy: Option<OverflowPolicy>,
+}
+
+#[derive(Debug, Clone)]
+enum Base {
+    Bytes(Vec<u8>),
+    Encoded(String),
+}
+
+impl CorrelationVectorBuilder {
+    /// A builder without a base, with a single `0` segment
+    pub fn new() -> CorrelationVectorBuilder {
+        Self::default()
+    }
+
+    /// Encode `base` as the base, as [`CorrelationVector::new_from_uuid`] does
+    pub fn base_uuid(self, base: Uuid) -> CorrelationVectorBuilder {
+        self.base_bytes(base.as_bytes())
+    }
+
+    /// Encode `base` as the base: 12 bytes for a v1 base, 16 bytes for a v2 or v3 base
+    pub fn base_bytes(mut self, base: &[u8]) -> CorrelationVectorBuilder {
+        self.base = Some(Base::Bytes(base.to_vec()));
+        self
+    }
+
+    /// Use an encoded base as it appears in the string representation, including the version
+    /// character of a v3 base
+    pub fn base(mut self, base: &str) -> CorrelationVectorBuilder {
+        self.base = Some(Base::Encoded(base.into()));
+        self
+    }
+
+    /// The version of the correlation vector. Defaults to the version of an encoded base, v1 for
+    /// a 12 byte base and v2 for a 16 byte base. Building fails with
+    /// [`CorrelationVectorParseError::VersionMismatch`] if the base belongs to another version.
+    pub fn version(mut self, version: CorrelationVectorVersion) -> CorrelationVectorBuilder {
+        self.version = Some(version);
+        self
+    }
+
+    /// The reset marker of a v3 correlation vector
+    pub fn reset(mut self, reset: u64) -> CorrelationVectorBuilder {
+        self.reset = Some(reset);
+        self
+    }
+
+    /// The segments of the vector clock, replacing the default `0` segment
+    pub fn segments(mut self, segments: impl IntoIterator<Item = u32>) -> CorrelationVectorBuilder {
+        self.segments = Some(segments.into_iter().collect());
+        self
+    }
+
+    /// Whether the correlation vector is terminated
+    pub fn immutable(mut self, immutable: bool) -> CorrelationVectorBuilder {
+        self.immutable = immutable;
+        self
+    }
+
+    /// The policy used when an operation would exceed the length limit, see
+    /// [`CorrelationVector::with_overflow_policy`]
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> CorrelationVectorBuilder {
+        self.overflow_policy = Some(policy);
+        self
+    }
+
+    /// Check the parts and build the correlation vector
+    pub fn build(&self) -> Result<CorrelationVector, CorrelationVectorParseError> {
+        let encoded;
+        let (version, base) = match &self.base {
+            None => return Err(CorrelationVectorParseError::MissingBase),
+            Some(Base::Encoded(base)) => {
+                let version = validate_base(base)?;
+                (version, base.as_str())
+            }
+            Some(Base::Bytes(bytes)) => {
+                let version = match (bytes.len(), self.version) {
+                    (12, _) => CorrelationVectorVersion::V1,
+                    (16, Some(CorrelationVectorVersion::V3)) => CorrelationVectorVersion::V3,
+                    (16, _) => CorrelationVectorVersion::V2,
+                    (length, version) => {
+                        let prefix = usize::from(version == Some(CorrelationVectorVersion::V3));
+                        return Err(CorrelationVectorParseError::InvalidBaseLength {
+                            length: prefix + (length * 4).div_ceil(3),
+                        });
+                    }
+                };
+                encoded = CorrelationVector::new_from_bytes(version, bytes);
+                (version, encoded.base())
+            }
+        };
+        if let Some(expected) = self.version {
+            if expected != version {
+                return Err(CorrelationVectorParseError::VersionMismatch {
+                    expected,
+                    actual: version,
+                });
+            }
+        }
+
+        if self.reset.is_some() && version != CorrelationVectorVersion::V3 {
+            return Err(CorrelationVectorParseError::InvalidReset);
+        }
+        if self.immutable && !version.supports_termination() {
+            return Err(CorrelationVectorParseError::UnexpectedTermination);
+        }
+        let segments = self.segments.as_deref().unwrap_or(&[0]);
+        if segments.is_empty() {
+            return Err(CorrelationVectorParseError::MissingVector);
+        }
+
+        let length = base.len()
+            + self.reset.map_or(0, |reset| hex_length_of(reset) + 1)
+            + segments
+                .iter()
+                .map(|&segment| serialized_length_of(segment) + 1)
+                .sum::<usize>();
+        if length > version.max_length() {
+            return Err(CorrelationVectorParseError::StringTooLongError);
+        }
+
+        let cv = CorrelationVector::from_parts(
+            version,
+            base,
+            self.reset,
+            segments.iter().copied(),
+            self.immutable,
+        );
+        Ok(match self.overflow_policy {
+            Some(policy) => cv.with_overflow_policy(policy),
+            None => cv,
+        })
+    }
+}
+
+/// The length of the reset marker's hexadecimal representation, without the `#`
+fn hex_length_of(reset: u64) -> usize {
+    let bits = (64 - reset.leading_zeros() as usize).max(1);
+    bits.div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn builds_from_parts() {
+        let uuid = Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+        let cv = CorrelationVectorBuilder::new()
+            .base_uuid(uuid)
+            .build()
+            .unwrap();
+        assert_eq!(cv, CorrelationVector::new_from_uuid(uuid));
+
+        let cv = CorrelationVectorBuilder::new()
+            .base("AP9v1ltK2S7qTS77z0lWtKg")
+            .reset(0)
+            .segments([4, 10])
+            .immutable(true)
+            .overflow_policy(OverflowPolicy::Error)
+            .build()
+            .unwrap();
+        assert_eq!(cv.to_string(), "AP9v1ltK2S7qTS77z0lWtKg#0.4.10!");
+        assert_eq!(cv.version(), CorrelationVectorVersion::V3);
+        assert_eq!(cv.overflow_policy(), OverflowPolicy::Error);
+
+        let cv = CorrelationVectorBuilder::new()
+            .base_bytes(&[0xff; 12])
+            .segments([1])
+            .build()
+            .unwrap();
+        assert_eq!(
+            cv,
+            CorrelationVector::parse_strict("////////////////.1").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_parts() {
+        let error = |builder: CorrelationVectorBuilder| builder.build().unwrap_err();
+        assert!(matches!(
+            error(CorrelationVectorBuilder::new()),
+            CorrelationVectorParseError::MissingBase
+        ));
+        assert!(matches!(
+            error(CorrelationVectorBuilder::new().base("P9v1ltK2S7qTS77z0lWt-g")),
+            CorrelationVectorParseError::InvalidBaseCharacter { character: '-' }
+        ));
+        assert!(matches!(
+            error(
+                CorrelationVectorBuilder::new()
+                    .base("P9v1ltK2S7qTS77z0lWtKg")
+                    .version(CorrelationVectorVersion::V3)
+            ),
+            CorrelationVectorParseError::VersionMismatch {
+                expected: CorrelationVectorVersion::V3,
+                actual: CorrelationVectorVersion::V2,
+            }
+        ));
+        assert!(matches!(
+            error(
+                CorrelationVectorBuilder::new()
+                    .base_bytes(&[0; 12])
+                    .version(CorrelationVectorVersion::V2)
+            ),
+            CorrelationVectorParseError::VersionMismatch {
+                expected: CorrelationVectorVersion::V2,
+                actual: CorrelationVectorVersion::V1,
+            }
+        ));
+        assert!(matches!(
+            error(
+                CorrelationVectorBuilder::new()
+                    .base_bytes(&[0; 16])
+                    .version(CorrelationVectorVersion::V1)
+            ),
+            CorrelationVectorParseError::VersionMismatch {
+                expected: CorrelationVectorVersion::V1,
+                actual: CorrelationVectorVersion::V2,
+            }
+        ));
+        assert!(matches!(
+            error(CorrelationVectorBuilder::new().base_bytes(&[0; 8])),
+            CorrelationVectorParseError::InvalidBaseLength { length: 11 }
+        ));
+        assert!(matches!(
+            error(
+                CorrelationVectorBuilder::new()
+                    .base("P9v1ltK2S7qTS77z0lWtKg")
+                    .reset(1)
+            ),
+            CorrelationVectorParseError::InvalidReset
+        ));
+        assert!(matches!(
+            error(
+                CorrelationVectorBuilder::new()
+                    .base("tul4NUsfs9Cl7mOf")
+                    .immutable(true)
+            ),
+            CorrelationVectorParseError::UnexpectedTermination
+        ));
+        assert!(matches!(
+            error(
+                CorrelationVectorBuilder::new()
+                    .base("P9v1ltK2S7qTS77z0lWtKg")
+                    .segments([])
+            ),
+            CorrelationVectorParseError::MissingVector
+        ));
+        assert!(matches!(
+            error(
+                CorrelationVectorBuilder::new()
+                    .base("P9v1ltK2S7qTS77z0lWtKg")
+                    .segments([u32::MAX; 10])
+            ),
+            CorrelationVectorParseError::StringTooLongError
+        ));
+    }
+
+    #[test]
+    fn length_limit_matches_extend() {
+        // 22 + 2 * 52 = 126 characters, one more segment does not fit
+        let builder = CorrelationVectorBuilder::new().base("P9v1ltK2S7qTS77z0lWtKg");
+        let mut cv = builder.clone().segments([0; 52]).build().unwrap();
+        assert!(builder.clone().segments([0; 53]).build().is_err());
+        assert!(cv.try_extend().is_err());
+
+        let cv = CorrelationVectorBuilder::new()
+            .base("AP9v1ltK2S7qTS77z0lWtKg")
+            .reset(u64::MAX)
+            .segments([0; 43])
+            .build()
+            .unwrap();
+        assert_eq!(cv.serialized_len(), 23 + 17 + 86);
+    }
+}
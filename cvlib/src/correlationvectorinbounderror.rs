@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+use crate::correlationvectorparsererror::CorrelationVectorParseError;
+
+/// The error type for [`CorrelationVector::from_inbound_with`](super::CorrelationVector::from_inbound_with())
+#[derive(Debug, Error)]
+pub enum CorrelationVectorInboundError {
+    /// The inbound correlation vector could not be parsed
+    #[error("Invalid inbound correlation vector")]
+    Invalid {
+        #[from]
+        source: CorrelationVectorParseError,
+    },
+    /// The inbound correlation vector is terminated
+    #[error("Inbound correlation vector is terminated")]
+    Terminated,
+}
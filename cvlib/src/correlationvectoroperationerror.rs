@@ -0,0 +1,16 @@
+use thiserror::Error;
+
+/// The error type for the fallible operations of [`CorrelationVector`](super::CorrelationVector),
+/// such as [`CorrelationVector::try_extend`](super::CorrelationVector::try_extend())
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum CorrelationVectorOperationError {
+    /// The correlation vector was already terminated, so it was left unchanged
+    #[error("Correlation vector is terminated")]
+    Terminated,
+    /// The operation would exceed the length limit of the correlation vector's version
+    #[error("Operation needs {required} more characters but only {remaining} are left")]
+    Overflow { required: usize, remaining: usize },
+    /// The latest clock is already u32::MAX and can not be incremented
+    #[error("Latest clock of correlation vector can not be incremented past u32::MAX")]
+    ClockOverflow,
+}
@@ -1,11 +1,16 @@
 use thiserror::Error;
 
+use crate::correlationvectorversion::CorrelationVectorVersion;
+
 /// The error type for [`CorrelationVector::parse`](super::CorrelationVector::parse())
 #[derive(Debug, Error)]
 pub enum CorrelationVectorParseError {
     /// The input is empty
     #[error("Empty input")]
     Empty,
+    /// No base was given to a [`CorrelationVectorBuilder`](super::CorrelationVectorBuilder)
+    #[error("Missing base of correlation vector")]
+    MissingBase,
     /// There was no vector clock in the input
     #[error("Missing vector portion of correlation vector")]
     MissingVector,
@@ -13,8 +18,34 @@ pub enum CorrelationVectorParseError {
     #[error("Invalid vector portion of correlation vector")]
     ParseError {
         #[from]
-        source: std::num::ParseIntError,
+        source: core::num::ParseIntError,
+    },
+    /// The base is not the length the specification requires
+    #[error("Invalid base length {length}")]
+    InvalidBaseLength { length: usize },
+    /// The base a [`CorrelationVectorBuilder`](super::CorrelationVectorBuilder) was given belongs
+    /// to a different version than the one it was asked to build
+    #[error("Base of a {actual:?} correlation vector given for a {expected:?} correlation vector")]
+    VersionMismatch {
+        expected: CorrelationVectorVersion,
+        actual: CorrelationVectorVersion,
     },
+    /// The base contains a character outside of the base64 alphabet
+    #[error("Invalid character '{character}' in base")]
+    InvalidBaseCharacter { character: char },
+    /// The last character of the base sets padding bits beyond the encoded bytes, so the base
+    /// does not decode
+    #[error("Invalid last character '{character}' in base")]
+    InvalidBasePadding { character: char },
+    /// A segment of the vector clock is not a canonical decimal u32
+    #[error("Invalid segment at index {index} of the vector portion of correlation vector")]
+    InvalidSegment { index: usize },
+    /// The reset marker is not a canonical lowercase hexadecimal u64 or the version has no reset
+    #[error("Invalid reset marker of correlation vector")]
+    InvalidReset,
+    /// The input is terminated but its version has no termination symbol
+    #[error("Termination symbol is not allowed for this version of correlation vector")]
+    UnexpectedTermination,
     /// The input is too long to form a valid correlation vector according to the specification
     #[error("String is too long to be a valid correlation vector")]
     StringTooLongError,
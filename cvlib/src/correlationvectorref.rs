@@ -0,0 +1,193 @@
+use core::fmt::{Display, Formatter};
+
+use crate::{
+    correlationvector::{
+        parse_reset, parse_segment, validate_base, CorrelationVector, MAX_INPUT_LENGTH,
+        RESET_SYMBOL, TERMINATION_SYMBOL,
+    },
+    correlationvectorparsererror::CorrelationVectorParseError,
+    correlationvectorversion::CorrelationVectorVersion,
+};
+
+/// A validated correlation vector borrowed from its string representation.
+///
+/// Parsing does not allocate, so it is suited to inspecting and forwarding headers. Use
+/// [`CorrelationVectorRef::to_correlation_vector`] to get a [`CorrelationVector`] that can be
+/// extended, incremented or spun.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CorrelationVectorRef<'a> {
+    input: &'a str,
+    version: CorrelationVectorVersion,
+    base: &'a str,
+    reset: Option<u64>,
+    vector: &'a str,
+    immutable: bool,
+}
+
+impl<'a> CorrelationVectorRef<'a> {
+    /// Validate a string representation of a CorrelationVector against the specification,
+    /// following the same rules as [`CorrelationVector::parse_strict`].
+    pub fn parse(input: &'a str) -> Result<CorrelationVectorRef<'a>, CorrelationVectorParseError> {
+        if input.is_empty() {
+            return Err(CorrelationVectorParseError::Empty);
+        }
+        if input.len() > MAX_INPUT_LENGTH {
+            return Err(CorrelationVectorParseError::StringTooLongError);
+        }
+
+        let (unterminated, immutable) = match input.strip_suffix(TERMINATION_SYMBOL) {
+            Some(rest) => (rest, true),
+            None => (input, false),
+        };
+
+        let (base, vector) = match unterminated.split_once('.') {
+            Some((base, vector)) => (base, Some(vector)),
+            None => (unterminated, None),
+        };
+        let (base, reset) = match base.split_once(RESET_SYMBOL) {
+            Some((base, reset)) => (base, Some(reset)),
+            None => (base, None),
+        };
+        let version = validate_base(base)?;
+        let reset = reset.map(|reset| parse_reset(version, reset)).transpose()?;
+        if immutable && !version.supports_termination() {
+            return Err(CorrelationVectorParseError::UnexpectedTermination);
+        }
+
+        let vector = vector.ok_or(CorrelationVectorParseError::MissingVector)?;
+        for (index, segment) in vector.split('.').enumerate() {
+            parse_segment(index, segment)?;
+        }
+        if unterminated.len() > version.max_length() {
+            return Err(CorrelationVectorParseError::StringTooLongError);
+        }
+
+        Ok(CorrelationVectorRef {
+            input,
+            version,
+            base,
+            reset,
+            vector,
+            immutable,
+        })
+    }
+
+    /// The string this correlation vector was parsed from
+    pub fn as_str(&self) -> &'a str {
+        self.input
+    }
+
+    /// The version of the specification this correlation vector follows
+    pub fn version(&self) -> CorrelationVectorVersion {
+        self.version
+    }
+
+    /// The base, including the version character of a v3 base but not the reset marker
+    pub fn base(&self) -> &'a str {
+        self.base
+    }
+
+    /// The value of the reset marker of a v3 correlation vector, see
+    /// [`CorrelationVector::reset_marker`]
+    pub fn reset_marker(&self) -> Option<u64> {
+        self.reset
+    }
+
+    /// The segments of the vector clock
+    pub fn segments(&self) -> impl Iterator<Item = u32> + 'a {
+        self.vector.split('.').map(|segment| {
+            segment
+                .parse()
+                .expect("Segments are validated when parsing")
+        })
+    }
+
+    /// The number of segments in the vector clock
+    pub fn depth(&self) -> usize {
+        self.vector.bytes().filter(|&b| b == b'.').count() + 1
+    }
+
+    /// Whether the correlation vector is terminated and can no longer change
+    pub fn is_immutable(&self) -> bool {
+        self.immutable
+    }
+
+    /// Copy into an owned [`CorrelationVector`]
+    pub fn to_correlation_vector(&self) -> CorrelationVector {
+        CorrelationVector::from_parts(
+            self.version,
+            self.base,
+            self.reset,
+            self.segments(),
+            self.immutable,
+        )
+    }
+}
+
+impl<'a> From<CorrelationVectorRef<'a>> for CorrelationVector {
+    fn from(cv: CorrelationVectorRef<'a>) -> Self {
+        cv.to_correlation_vector()
+    }
+}
+
+impl Display for CorrelationVectorRef<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), core::fmt::Error> {
+        f.write_str(self.input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn parse_exposes_parts() {
+        let cv = CorrelationVectorRef::parse("P9v1ltK2S7qTS77z0lWtKg.0.10.3!").unwrap();
+        assert_eq!(cv.version(), CorrelationVectorVersion::V2);
+        assert_eq!(cv.base(), "P9v1ltK2S7qTS77z0lWtKg");
+        assert_eq!(cv.reset_marker(), None);
+        assert_eq!(cv.segments().collect::<Vec<u32>>(), vec![0, 10, 3]);
+        assert_eq!(cv.depth(), 3);
+        assert!(cv.is_immutable());
+        assert_eq!(cv.to_string(), "P9v1ltK2S7qTS77z0lWtKg.0.10.3!");
+
+        let cv = CorrelationVectorRef::parse("AP9v1ltK2S7qTS77z0lWtKg#1f.0").unwrap();
+        assert_eq!(cv.version(), CorrelationVectorVersion::V3);
+        assert_eq!(cv.base(), "AP9v1ltK2S7qTS77z0lWtKg");
+        assert_eq!(cv.reset_marker(), Some(0x1f));
+        assert_eq!(cv.depth(), 1);
+        assert!(!cv.is_immutable());
+    }
+
+    #[test]
+    fn converts_to_owned() {
+        for input in [
+            "P9v1ltK2S7qTS77z0lWtKg.0.10.3!",
+            "AP9v1ltK2S7qTS77z0lWtKg#1f.0",
+            "tul4NUsfs9Cl7mOf.1.2",
+        ] {
+            let cv = CorrelationVectorRef::parse(input).unwrap();
+            let owned = CorrelationVector::from(cv);
+            assert_eq!(owned, CorrelationVector::parse_strict(input).unwrap());
+            assert_eq!(owned.to_string(), input);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid_input() {
+        for input in [
+            "",
+            "base.0",
+            "P9v1ltK2S7qTS77z0lWtKg",
+            "P9v1ltK2S7qTS77z0lWtKg.",
+            "P9v1ltK2S7qTS77z0lWtKg.01",
+            "P9v1ltK2S7qTS77z0lWtKg#1.0",
+            "tul4NUsfs9Cl7mOf.0!",
+        ] {
+            assert!(CorrelationVectorRef::parse(input).is_err(), "{}", input);
+        }
+    }
+}
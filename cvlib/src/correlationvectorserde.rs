@@ -0,0 +1,60 @@
+use core::fmt::Formatter;
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::correlationvector::CorrelationVector;
+
+/// Serializes as the string representation, e.g. `P9v1ltK2S7qTS77z0lWtKg.1.2`
+impl Serialize for CorrelationVector {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserializes from the string representation with [`CorrelationVector::parse_strict`]
+impl<'de> Deserialize<'de> for CorrelationVector {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(CorrelationVectorVisitor)
+    }
+}
+
+struct CorrelationVectorVisitor;
+
+impl<'de> Visitor<'de> for CorrelationVectorVisitor {
+    type Value = CorrelationVector;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("a correlation vector string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        CorrelationVector::parse_strict(value).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_as_string() {
+        let cv = CorrelationVector::parse_strict("P9v1ltK2S7qTS77z0lWtKg.1.2!").unwrap();
+        let json = serde_json::to_string(&cv).unwrap();
+        assert_eq!(json, r#""P9v1ltK2S7qTS77z0lWtKg.1.2!""#);
+        assert_eq!(
+            serde_json::from_str::<CorrelationVector>(&json).unwrap(),
+            cv
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_string() {
+        let error = serde_json::from_str::<CorrelationVector>(r#""P9v1ltK2S7qTS77z0lWtKg.01""#)
+            .unwrap_err();
+        assert!(error.to_string().contains("Invalid segment"), "{}", error);
+        assert!(serde_json::from_str::<CorrelationVector>("1").is_err());
+    }
+}
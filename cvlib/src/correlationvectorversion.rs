@@ -0,0 +1,50 @@
+/// The version of the correlation vector specification a [`CorrelationVector`](crate::CorrelationVector) follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CorrelationVectorVersion {
+    /// 16 character base, at most 63 characters and no termination symbol
+    V1,
+    /// 22 character base, at most 127 characters plus the termination symbol
+    V2,
+    /// The version character `A` followed by a 22 character base, at most 127 characters
+    /// plus the termination symbol. Overflowing v3 correlation vectors are reset.
+    V3,
+}
+
+impl CorrelationVectorVersion {
+    /// Detect the version from the length of a base
+    pub(crate) fn from_base_length(length: usize) -> Option<CorrelationVectorVersion> {
+        match length {
+            16 => Some(CorrelationVectorVersion::V1),
+            22 => Some(CorrelationVectorVersion::V2),
+            23 => Some(CorrelationVectorVersion::V3),
+            _ => None,
+        }
+    }
+
+    /// The number of characters in the base, including the version character of v3
+    pub fn base_length(self) -> usize {
+        match self {
+            CorrelationVectorVersion::V1 => 16,
+            CorrelationVectorVersion::V2 => 22,
+            CorrelationVectorVersion::V3 => 23,
+        }
+    }
+
+    /// The maximum length of the serialized correlation vector, excluding the termination symbol
+    pub fn max_length(self) -> usize {
+        match self {
+            CorrelationVectorVersion::V1 => 63,
+            CorrelationVectorVersion::V2 | CorrelationVectorVersion::V3 => 127,
+        }
+    }
+
+    /// Whether a correlation vector of this version can be terminated with `!`.
+    /// v1 drops operations that would overflow instead.
+    pub fn supports_termination(self) -> bool {
+        match self {
+            CorrelationVectorVersion::V1 => false,
+            CorrelationVectorVersion::V2 | CorrelationVectorVersion::V3 => true,
+        }
+    }
+}
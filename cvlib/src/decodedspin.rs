@@ -0,0 +1,88 @@
+use core::{convert::TryFrom, ops::Range, time::Duration};
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+use crate::{
+    spinlayout::SpinLayout,
+    spinparams::{epoch_offset_ticks, low_bits_mask, SpinMode},
+};
+
+/// A spun value of a vector clock, decoded with the [`SpinParams`](crate::SpinParams) or
+/// [`SpinLayout`] used to produce it.
+/// See [`CorrelationVector::decode_spin`](crate::CorrelationVector::decode_spin()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedSpin {
+    /// The index of the first segment of the spun value in the vector clock
+    pub index: usize,
+    /// The time since the UNIX epoch during which the spin happened, accurate to the
+    /// counter interval. `None` if the spin used no counter bits.
+    pub window: Option<Range<Duration>>,
+    /// The entropy bits of the spun value
+    pub entropy: u64,
+}
+
+impl DecodedSpin {
+    /// The [`window`](DecodedSpin::window) as wall-clock times
+    #[cfg(feature = "std")]
+    pub fn system_time_window(&self) -> Option<Range<SystemTime>> {
+        self.window.as_ref().map(|window| {
+            SystemTime::UNIX_EPOCH + window.start..SystemTime::UNIX_EPOCH + window.end
+        })
+    }
+}
+
+/// Decode the spun value starting at `index` of `segments`, spun with `layout` in `mode`.
+///
+/// The counter only keeps the low bits of the timestamp, so the window is the latest one
+/// matching the counter that does not start after `now_ticks`, the 100ns ticks since the UNIX
+/// epoch.
+pub(crate) fn decode_spin(
+    layout: SpinLayout,
+    mode: SpinMode,
+    segments: &[u32],
+    index: usize,
+    now_ticks: u128,
+) -> Option<DecodedSpin> {
+    let value = match (layout.segment_count(), mode) {
+        (1, _) => u64::from(*segments.get(index)?),
+        (_, SpinMode::Unix) => join(*segments.get(index + 1)?, *segments.get(index)?),
+        (_, SpinMode::Reference) => join(*segments.get(index)?, *segments.get(index + 1)?),
+    };
+
+    let entropy_bits = u32::from(layout.entropy_bits());
+    let counter_bits = u32::from(layout.counter_bits());
+    let entropy = value & low_bits_mask(entropy_bits);
+    let counter = value.checked_shr(entropy_bits).unwrap_or(0) & low_bits_mask(counter_bits);
+
+    let drop = u32::from(layout.ticks_to_drop());
+    let window = if counter_bits == 0 {
+        None
+    } else {
+        let offset = epoch_offset_ticks(mode);
+        let now = (now_ticks + offset) >> drop;
+        let period = 1u128 << counter_bits;
+        let mut candidate = (now & !(period - 1)) | u128::from(counter);
+        if candidate > now {
+            candidate = candidate.checked_sub(period)?;
+        }
+        let start = (candidate << drop).checked_sub(offset)?;
+        let end = ((candidate + 1) << drop).checked_sub(offset)?;
+        Some(ticks_to_duration(start)..ticks_to_duration(end))
+    };
+
+    Some(DecodedSpin {
+        index,
+        window,
+        entropy,
+    })
+}
+
+fn join(high: u32, low: u32) -> u64 {
+    (u64::from(high) << 32) | u64::from(low)
+}
+
+fn ticks_to_duration(ticks: u128) -> Duration {
+    let secs = u64::try_from(ticks / 10_000_000).unwrap_or(u64::MAX);
+    let nanos = (ticks % 10_000_000) as u32 * 100;
+    Duration::new(secs, nanos)
+}
@@ -0,0 +1,21 @@
+#[cfg(feature = "std")]
+use rand::RngCore;
+
+/// A source of random bytes, used for new bases and by
+/// [`CorrelationVector::spin_with`](crate::CorrelationVector::spin_with())
+pub trait EntropySource {
+    /// Fill `dest` with random bytes
+    fn fill_bytes(&mut self, dest: &mut [u8]);
+}
+
+/// The thread-local random number generator of `rand`, used by default
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomEntropy;
+
+#[cfg(feature = "std")]
+impl EntropySource for RandomEntropy {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand::thread_rng().fill_bytes(dest);
+    }
+}
@@ -3,6 +3,7 @@
 //! Learn more about the methods in the [Readme](https://github.com/TransientError/CorrelationVector-rust/blob/master/cvlib/Readme.md)
 //! or in the [specification](https://github.com/microsoft/CorrelationVector).
 //! ```rust
+//! # #[cfg(feature = "std")] {
 //! use cvlib::CorrelationVector;
 //!
 //! let mut cv = CorrelationVector::new(); // e.g. wC71fJEqSPuHrPQ9ZoXrKg.0
@@ -11,12 +12,68 @@
 //! let cv_string = cv.to_string(); // create string representation of CV
 //!
 //! let cv_parsed = CorrelationVector::parse(&cv_string); // parse the string representation of the correlation vector
+//! # }
 //! ```
+//!
+//! The default `std` feature provides the system clock and a random number generator. Without it
+//! the crate is `no_std` and needs `alloc`; time and entropy then come from a [`Clock`] and an
+//! [`EntropySource`], e.g. [`CorrelationVector::new_with_entropy`] and
+//! [`CorrelationVector::spin_with`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+mod boundary;
+mod children;
+mod clock;
 mod correlationvector;
+mod correlationvectorbuilder;
+mod correlationvectorinbounderror;
+mod correlationvectoroperationerror;
 mod correlationvectorparsererror;
+mod correlationvectorref;
+#[cfg(feature = "serde")]
+mod correlationvectorserde;
+mod correlationvectorversion;
+mod decodedspin;
+mod entropysource;
+mod lineage;
+mod ordering;
+mod overflowpolicy;
+mod sharedcorrelationvector;
+mod spinlayout;
 mod spinparams;
+mod spinparamserror;
+mod spinparamsparseerror;
+#[cfg(feature = "serde")]
+pub mod structured;
+mod sync;
+#[cfg(test)]
+mod testutil;
 
-pub use correlationvector::CorrelationVector;
+pub use boundary::{InboundPolicy, InvalidInbound, TerminatedInbound, CORRELATION_VECTOR_HEADER};
+pub use children::Children;
+pub use clock::Clock;
+#[cfg(feature = "std")]
+pub use clock::SystemClock;
+pub use correlationvector::{CorrelationVector, KEY_NAMESPACE};
+pub use correlationvectorbuilder::CorrelationVectorBuilder;
+pub use correlationvectorinbounderror::CorrelationVectorInboundError;
+pub use correlationvectoroperationerror::CorrelationVectorOperationError;
 pub use correlationvectorparsererror::CorrelationVectorParseError;
-pub use spinparams::{SpinCounterInterval, SpinCounterPeriodicity, SpinEntropy, SpinParams};
+pub use correlationvectorref::CorrelationVectorRef;
+pub use correlationvectorversion::CorrelationVectorVersion;
+pub use decodedspin::DecodedSpin;
+pub use entropysource::EntropySource;
+#[cfg(feature = "std")]
+pub use entropysource::RandomEntropy;
+pub use lineage::{Ancestors, RelativePath};
+pub use overflowpolicy::OverflowPolicy;
+pub use sharedcorrelationvector::SharedCorrelationVector;
+pub use spinlayout::SpinLayout;
+pub use spinparams::{
+    SpinCounterInterval, SpinCounterPeriodicity, SpinEntropy, SpinMode, SpinParams,
+};
+pub use spinparamserror::SpinParamsError;
+pub use spinparamsparseerror::SpinParamsParseError;
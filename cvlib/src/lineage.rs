@@ -0,0 +1,202 @@
+use alloc::vec::Vec;
+
+use crate::correlationvector::{CorrelationVector, TERMINATION_SYMBOL};
+
+/// The path between two correlation vectors of the same lineage, see
+/// [`CorrelationVector::relative_path`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RelativePath {
+    /// The number of hops up from the start to the lowest common ancestor
+    pub up: usize,
+    /// The segments appended below the lowest common ancestor to reach the end
+    pub down: Vec<u32>,
+}
+
+/// An iterator over the ancestors of a correlation vector, from its parent up to the root.
+/// See [`CorrelationVector::ancestors`].
+#[derive(Debug, Clone)]
+pub struct Ancestors<'a> {
+    cv: &'a CorrelationVector,
+    depth: usize,
+}
+
+impl Iterator for Ancestors<'_> {
+    type Item = CorrelationVector;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.depth <= 1 {
+            return None;
+        }
+        self.depth -= 1;
+        Some(self.cv.truncated(self.depth))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.depth.saturating_sub(1);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Ancestors<'_> {}
+
+/// Extending a correlation vector appends a segment for a child, incrementing changes the last
+/// segment to move on to the next sibling. So one correlation vector is an ancestor of another
+/// if they share a base and reset marker and its segments are a proper prefix of the other's,
+/// e.g. `base.1` is the parent of `base.1.0` but not of `base.2.0`. Spun values are segments
+/// like any other. Termination does not change the lineage.
+impl CorrelationVector {
+    /// The correlation vector that was extended to create this one, i.e. this one without its
+    /// last segment. Returns `None` for a root with a single segment.
+    pub fn parent(&self) -> Option<CorrelationVector> {
+        self.ancestors().next()
+    }
+
+    /// The ancestors of this correlation vector, from its parent up to the root
+    pub fn ancestors(&self) -> Ancestors<'_> {
+        Ancestors {
+            cv: self,
+            depth: self.depth(),
+        }
+    }
+
+    /// Whether `other` descends from this correlation vector through one or more extensions
+    pub fn is_ancestor_of(&self, other: &CorrelationVector) -> bool {
+        let ancestor = self.as_str().trim_end_matches(TERMINATION_SYMBOL);
+        let descendant = other.as_str().trim_end_matches(TERMINATION_SYMBOL);
+        descendant.len() > ancestor.len()
+            && descendant.starts_with(ancestor)
+            && descendant.as_bytes()[ancestor.len()] == b'.'
+    }
+
+    /// Whether this correlation vector descends from `other` through one or more extensions
+    pub fn is_descendant_of(&self, other: &CorrelationVector) -> bool {
+        other.is_ancestor_of(self)
+    }
+
+    /// The deepest correlation vector that is this one or one of its ancestors, and also
+    /// `other` or one of its ancestors. Returns `None` if they do not share a root.
+    pub fn lowest_common_ancestor(&self, other: &CorrelationVector) -> Option<CorrelationVector> {
+        match self.common_depth(other) {
+            0 => None,
+            depth => Some(self.truncated(depth)),
+        }
+    }
+
+    /// The path from this correlation vector to `other` through their lowest common ancestor.
+    /// Returns `None` if they do not share a root.
+    pub fn relative_path(&self, other: &CorrelationVector) -> Option<RelativePath> {
+        match self.common_depth(other) {
+            0 => None,
+            depth => Some(RelativePath {
+                up: self.depth() - depth,
+                down: other.segments().skip(depth).collect(),
+            }),
+        }
+    }
+
+    /// The number of leading segments two correlation vectors of the same lineage share
+    fn common_depth(&self, other: &CorrelationVector) -> usize {
+        if self.base() != other.base() || self.reset_marker() != other.reset_marker() {
+            return 0;
+        }
+        self.segments()
+            .zip(other.segments())
+            .take_while(|(a, b)| a == b)
+            .count()
+    }
+
+    /// The ancestor, or this correlation vector itself, with `depth` segments
+    fn truncated(&self, depth: usize) -> CorrelationVector {
+        CorrelationVector::from_parts(
+            self.version(),
+            self.base(),
+            self.reset_marker(),
+            self.segments().take(depth),
+            false,
+        )
+        .with_settings_of(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use super::*;
+    use crate::testutil::cv;
+
+    #[test]
+    fn parent_and_ancestors() {
+        let child = cv("P9v1ltK2S7qTS77z0lWtKg.1.2.3!");
+        assert_eq!(child.parent(), Some(cv("P9v1ltK2S7qTS77z0lWtKg.1.2")));
+        assert_eq!(
+            child.ancestors().map(|a| a.to_string()).collect::<Vec<_>>(),
+            vec!["P9v1ltK2S7qTS77z0lWtKg.1.2", "P9v1ltK2S7qTS77z0lWtKg.1"]
+        );
+        assert_eq!(child.ancestors().len(), 2);
+        assert_eq!(cv("P9v1ltK2S7qTS77z0lWtKg.1").parent(), None);
+
+        let reset = cv("AP9v1ltK2S7qTS77z0lWtKg#1f.4.5");
+        assert_eq!(reset.parent(), Some(cv("AP9v1ltK2S7qTS77z0lWtKg#1f.4")));
+    }
+
+    #[test]
+    fn ancestor_and_descendant() {
+        let parent = cv("P9v1ltK2S7qTS77z0lWtKg.1");
+        let child = cv("P9v1ltK2S7qTS77z0lWtKg.1.0");
+        let grandchild = cv("P9v1ltK2S7qTS77z0lWtKg.1.0.7!");
+        assert!(parent.is_ancestor_of(&child));
+        assert!(parent.is_ancestor_of(&grandchild));
+        assert!(grandchild.is_descendant_of(&parent));
+        assert!(!child.is_ancestor_of(&parent));
+        assert!(!parent.is_ancestor_of(&parent));
+
+        // increments are siblings, not children
+        assert!(!parent.is_ancestor_of(&cv("P9v1ltK2S7qTS77z0lWtKg.2.0")));
+        assert!(!parent.is_ancestor_of(&cv("P9v1ltK2S7qTS77z0lWtKg.10")));
+        assert!(!parent.is_ancestor_of(&cv("P9v1ltK2S7qTS77z0lWtKg.10.0")));
+        assert!(!parent.is_ancestor_of(&cv("Q9v1ltK2S7qTS77z0lWtKg.1.0")));
+        assert!(!cv("AP9v1ltK2S7qTS77z0lWtKg#1f.1")
+            .is_ancestor_of(&cv("AP9v1ltK2S7qTS77z0lWtKg#2f.1.0")));
+    }
+
+    #[test]
+    fn lowest_common_ancestor_and_relative_path() {
+        let a = cv("P9v1ltK2S7qTS77z0lWtKg.1.2.3");
+        let b = cv("P9v1ltK2S7qTS77z0lWtKg.1.2.4.0!");
+        assert_eq!(
+            a.lowest_common_ancestor(&b),
+            Some(cv("P9v1ltK2S7qTS77z0lWtKg.1.2"))
+        );
+        assert_eq!(
+            a.relative_path(&b),
+            Some(RelativePath {
+                up: 1,
+                down: vec![4, 0]
+            })
+        );
+
+        let parent = cv("P9v1ltK2S7qTS77z0lWtKg.1");
+        assert_eq!(parent.lowest_common_ancestor(&a), Some(parent.clone()));
+        assert_eq!(
+            parent.relative_path(&a),
+            Some(RelativePath {
+                up: 0,
+                down: vec![2, 3]
+            })
+        );
+        assert_eq!(
+            a.relative_path(&parent),
+            Some(RelativePath {
+                up: 2,
+                down: vec![]
+            })
+        );
+
+        assert_eq!(
+            a.lowest_common_ancestor(&cv("P9v1ltK2S7qTS77z0lWtKg.2")),
+            None
+        );
+        assert_eq!(a.relative_path(&cv("Q9v1ltK2S7qTS77z0lWtKg.1")), None);
+    }
+}
@@ -0,0 +1,170 @@
+use core::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use crate::correlationvector::CorrelationVector;
+
+/// Correlation vectors are equal if they have the same version and string representation. The
+/// [`OverflowPolicy`](crate::OverflowPolicy) and
+/// [`predecessor`](CorrelationVector::predecessor) are not compared.
+impl PartialEq for CorrelationVector {
+    fn eq(&self, other: &Self) -> bool {
+        self.version() == other.version() && self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for CorrelationVector {}
+
+impl Hash for CorrelationVector {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.version().hash(state);
+        self.as_str().hash(state);
+    }
+}
+
+impl PartialOrd for CorrelationVector {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders correlation vectors by base and reset marker, then in depth-first pre-order of their
+/// segments compared as numbers, e.g. `base.1`, `base.1.0`, `base.1.1`, `base.2`, `base.10`.
+/// This is the order in which one correlation vector's calls were made, see
+/// [`CorrelationVector::causal_cmp`] for the order in which its events happened.
+impl Ord for CorrelationVector {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.base()
+            .cmp(other.base())
+            .then_with(|| self.reset_marker().cmp(&other.reset_marker()))
+            .then_with(|| self.segments().cmp(other.segments()))
+            .then_with(|| self.is_immutable().cmp(&other.is_immutable()))
+            .then_with(|| self.version().cmp(&other.version()))
+    }
+}
+
+impl CorrelationVector {
+    /// Compare two correlation vectors by the happens-before relation, ignoring termination.
+    ///
+    /// A correlation vector happens before its descendants, before its later siblings and their
+    /// descendants, e.g. `base.1` happens before `base.1.0`, `base.2` and `base.2.5`. Events
+    /// of different calls, such as `base.1.0` and `base.2`, and correlation vectors with
+    /// different bases or reset markers are concurrent and return `None`.
+    pub fn causal_cmp(&self, other: &CorrelationVector) -> Option<Ordering> {
+        if self.base() != other.base() || self.reset_marker() != other.reset_marker() {
+            return None;
+        }
+        if self.happens_before(other) {
+            Some(Ordering::Less)
+        } else if other.happens_before(self) {
+            Some(Ordering::Greater)
+        } else if self.segments().eq(other.segments()) {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+
+    /// Whether this correlation vector happens strictly before `other`, see
+    /// [`CorrelationVector::causal_cmp`]
+    pub fn happens_before(&self, other: &CorrelationVector) -> bool {
+        if self.base() != other.base() || self.reset_marker() != other.reset_marker() {
+            return false;
+        }
+        let depth = self.depth();
+        if other.depth() < depth {
+            return false;
+        }
+        let mut segments = self.segments().zip(other.segments()).take(depth);
+        let shared_prefix = segments.by_ref().take(depth - 1).all(|(a, b)| a == b);
+        match segments.next() {
+            Some((last, other_last)) => {
+                shared_prefix
+                    && (last < other_last || (last == other_last && other.depth() > depth))
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::*;
+    use crate::{testutil::cv, OverflowPolicy};
+
+    #[test]
+    fn sorts_in_pre_order() {
+        let mut cvs = [
+            "P9v1ltK2S7qTS77z0lWtKg.10",
+            "P9v1ltK2S7qTS77z0lWtKg.2",
+            "P9v1ltK2S7qTS77z0lWtKg.1.1",
+            "P9v1ltK2S7qTS77z0lWtKg.1",
+            "P9v1ltK2S7qTS77z0lWtKg.1.0.3!",
+            "P9v1ltK2S7qTS77z0lWtKg.1.0",
+        ]
+        .iter()
+        .map(|input| cv(input))
+        .collect::<Vec<_>>();
+        cvs.sort();
+        assert_eq!(
+            cvs.iter().map(|cv| cv.as_str()).collect::<Vec<_>>(),
+            vec![
+                "P9v1ltK2S7qTS77z0lWtKg.1",
+                "P9v1ltK2S7qTS77z0lWtKg.1.0",
+                "P9v1ltK2S7qTS77z0lWtKg.1.0.3!",
+                "P9v1ltK2S7qTS77z0lWtKg.1.1",
+                "P9v1ltK2S7qTS77z0lWtKg.2",
+                "P9v1ltK2S7qTS77z0lWtKg.10",
+            ]
+        );
+    }
+
+    #[test]
+    fn equality_and_hash_ignore_policy() {
+        let a = cv("P9v1ltK2S7qTS77z0lWtKg.1.2");
+        let b = cv("P9v1ltK2S7qTS77z0lWtKg.1.2").with_overflow_policy(OverflowPolicy::Rebase);
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+        #[cfg(feature = "std")]
+        {
+            let set = [a, b]
+                .iter()
+                .cloned()
+                .collect::<std::collections::HashSet<_>>();
+            assert_eq!(set.len(), 1);
+        }
+        assert_ne!(
+            cv("P9v1ltK2S7qTS77z0lWtKg.1.2"),
+            cv("P9v1ltK2S7qTS77z0lWtKg.1.2!")
+        );
+    }
+
+    #[test]
+    fn happens_before() {
+        let parent = cv("P9v1ltK2S7qTS77z0lWtKg.1");
+        for later in [
+            "P9v1ltK2S7qTS77z0lWtKg.1.0",
+            "P9v1ltK2S7qTS77z0lWtKg.2",
+            "P9v1ltK2S7qTS77z0lWtKg.10.5!",
+        ] {
+            let later = cv(later);
+            assert!(parent.happens_before(&later), "{}", later);
+            assert_eq!(parent.causal_cmp(&later), Some(Ordering::Less));
+            assert_eq!(later.causal_cmp(&parent), Some(Ordering::Greater));
+        }
+        assert_eq!(
+            parent.causal_cmp(&cv("P9v1ltK2S7qTS77z0lWtKg.1!")),
+            Some(Ordering::Equal)
+        );
+        for concurrent in ["P9v1ltK2S7qTS77z0lWtKg.0.3", "Q9v1ltK2S7qTS77z0lWtKg.2"] {
+            assert_eq!(parent.causal_cmp(&cv(concurrent)), None);
+        }
+        assert_eq!(
+            cv("P9v1ltK2S7qTS77z0lWtKg.1.0").causal_cmp(&cv("P9v1ltK2S7qTS77z0lWtKg.2")),
+            None
+        );
+    }
+}
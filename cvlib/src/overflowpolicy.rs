@@ -0,0 +1,31 @@
+use crate::correlationvectorversion::CorrelationVectorVersion;
+
+/// What a [`CorrelationVector`](crate::CorrelationVector) does when an operation would exceed the
+/// length limit of its version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Terminate the correlation vector with `!`, so it can no longer change.
+    /// v1 has no termination symbol and drops the operation instead, as with `Error`.
+    Terminate,
+    /// Drop the operation and leave the correlation vector unchanged and mutable
+    Error,
+    /// Replace the vector clock with a fresh clock under a new reset marker, see
+    /// [`CorrelationVector::reset`](crate::CorrelationVector::reset()).
+    /// Only v3 can be reset, other versions fall back to `Terminate`.
+    Reset,
+    /// Start over with a new random base of the same version, keeping the overflowing correlation
+    /// vector as its [`predecessor`](crate::CorrelationVector::predecessor())
+    Rebase,
+}
+
+impl OverflowPolicy {
+    /// The policy the specification prescribes for a version: v1 drops the operation, v2
+    /// terminates and v3 resets
+    pub fn default_for_version(version: CorrelationVectorVersion) -> OverflowPolicy {
+        match version {
+            CorrelationVectorVersion::V1 => OverflowPolicy::Error,
+            CorrelationVectorVersion::V2 => OverflowPolicy::Terminate,
+            CorrelationVectorVersion::V3 => OverflowPolicy::Reset,
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use core::{
+    convert::TryFrom,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    clock::Clock,
+    correlationvector::{serialized_length_of, CorrelationVector},
+    entropysource::EntropySource,
+    sync::{into_inner, lock, Mutex},
+};
+
+/// A correlation vector shared by the concurrent handlers of one request.
+///
+/// Each call to [`SharedCorrelationVector::next_outbound`] claims the next increment and returns
+/// its own copy, so every handler sends a unique correlation vector. Increments are claimed with
+/// an atomic counter over the shared prefix; only once the increments that fit in the length
+/// limit are used up is the [`OverflowPolicy`](crate::OverflowPolicy) applied under a lock.
+#[derive(Debug)]
+pub struct SharedCorrelationVector {
+    initial: CorrelationVector,
+    /// The number of increments claimed so far
+    claimed: AtomicUsize,
+    /// The number of increments that fit in the length limit
+    limit: usize,
+    /// The correlation vector past `limit`, once an increment overflowed
+    overflowed: Mutex<Option<CorrelationVector>>,
+}
+
+impl SharedCorrelationVector {
+    /// Share `cv`, typically the result of
+    /// [`CorrelationVector::from_inbound`](crate::CorrelationVector::from_inbound())
+    pub fn new(cv: CorrelationVector) -> SharedCorrelationVector {
+        SharedCorrelationVector {
+            limit: increment_limit(&cv),
+            initial: cv,
+            claimed: AtomicUsize::new(0),
+            overflowed: Mutex::new(None),
+        }
+    }
+
+    /// Increment the shared correlation vector for an outbound request and return the
+    /// incremented value to send.
+    ///
+    /// At the length limit the [`OverflowPolicy`](crate::OverflowPolicy) is applied exactly as
+    /// [`CorrelationVector::increment`] applies it, e.g. every later call returns the terminated
+    /// correlation vector.
+    pub fn next_outbound(&self) -> CorrelationVector {
+        self.claim()
+            .unwrap_or_else(|| self.overflow(CorrelationVector::increment))
+    }
+
+    /// Claim the next outbound correlation vector as
+    /// [`SharedCorrelationVector::next_outbound`] does, reading the time and entropy the
+    /// [`OverflowPolicy`](crate::OverflowPolicy) may need from `clock` and `entropy`
+    pub fn next_outbound_with(
+        &self,
+        clock: &impl Clock,
+        entropy: &mut impl EntropySource,
+    ) -> CorrelationVector {
+        self.claim()
+            .unwrap_or_else(|| self.overflow(|cv| cv.increment_with(clock, entropy)))
+    }
+
+    /// A copy of the current value, the last one handed out by
+    /// [`SharedCorrelationVector::next_outbound`]
+    pub fn current(&self) -> CorrelationVector {
+        match &*lock(&self.overflowed) {
+            Some(cv) => cv.clone(),
+            None => incremented(
+                self.initial.clone(),
+                self.claimed.load(Ordering::Relaxed).min(self.limit),
+            ),
+        }
+    }
+
+    /// Stop sharing and return the current value
+    pub fn into_inner(self) -> CorrelationVector {
+        let claimed = self.claimed.into_inner().min(self.limit);
+        let initial = self.initial;
+        into_inner(self.overflowed).unwrap_or_else(|| incremented(initial, claimed))
+    }
+
+    /// Claim the next increment, if it fits in the length limit
+    fn claim(&self) -> Option<CorrelationVector> {
+        let claimed = self
+            .claimed
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |claimed| {
+                claimed.checked_add(1)
+            })
+            .unwrap_or(usize::MAX);
+        (claimed < self.limit).then(|| incremented(self.initial.clone(), claimed + 1))
+    }
+
+    /// Apply `increment` to the correlation vector past the length limit
+    fn overflow(&self, increment: impl FnOnce(&mut CorrelationVector)) -> CorrelationVector {
+        let mut overflowed = lock(&self.overflowed);
+        let cv = overflowed.get_or_insert_with(|| incremented(self.initial.clone(), self.limit));
+        increment(cv);
+        cv.clone()
+    }
+}
+
+impl From<CorrelationVector> for SharedCorrelationVector {
+    fn from(cv: CorrelationVector) -> Self {
+        SharedCorrelationVector::new(cv)
+    }
+}
+
+/// `cv` incremented `increments` times, at most [`increment_limit`] times
+fn incremented(mut cv: CorrelationVector, increments: usize) -> CorrelationVector {
+    if increments > 0 {
+        cv.set_last_segment(cv.last_segment() + increments as u32);
+    }
+    cv
+}
+
+/// The number of times `cv` can be incremented before it exceeds the length limit or the clock
+/// overflows
+fn increment_limit(cv: &CorrelationVector) -> usize {
+    if cv.is_immutable() {
+        return 0;
+    }
+    let last = cv.last_segment();
+    let digits = serialized_length_of(last) + cv.remaining_capacity();
+    let max = 10u64
+        .checked_pow(digits as u32)
+        .map_or(u64::from(u32::MAX), |power| {
+            (power - 1).min(u64::from(u32::MAX))
+        });
+    usize::try_from(max - u64::from(last)).unwrap_or(usize::MAX)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::{collections::HashSet, sync::Arc, thread};
+
+    use alloc::{format, vec::Vec};
+
+    use super::*;
+
+    #[test]
+    fn next_outbound_is_unique_across_threads() {
+        let shared = Arc::new(SharedCorrelationVector::new(
+            CorrelationVector::parse_strict("P9v1ltK2S7qTS77z0lWtKg.0").unwrap(),
+        ));
+        let threads = (0..4)
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || (0..250).map(|_| shared.next_outbound()).collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+
+        let mut outbound = HashSet::new();
+        for thread in threads {
+            for cv in thread.join().unwrap() {
+                assert!(outbound.insert(cv));
+            }
+        }
+        assert_eq!(outbound.len(), 1000);
+        assert_eq!(
+            shared.current(),
+            CorrelationVector::parse_strict("P9v1ltK2S7qTS77z0lWtKg.1000").unwrap()
+        );
+    }
+
+    #[test]
+    fn next_outbound_applies_overflow_policy() {
+        let input = format!("P9v1ltK2S7qTS77z0lWtKg{}.99", ".0".repeat(51));
+        let shared = SharedCorrelationVector::from(CorrelationVector::parse(&input).unwrap());
+        let terminated = format!("{}!", input);
+        assert_eq!(shared.next_outbound().as_str(), terminated);
+        assert_eq!(shared.next_outbound().as_str(), terminated);
+        assert_eq!(shared.into_inner().as_str(), terminated);
+    }
+
+    #[test]
+    fn next_outbound_continues_after_reset() {
+        let input = format!("AP9v1ltK2S7qTS77z0lWtKg{}.8", ".0".repeat(51));
+        let shared = SharedCorrelationVector::new(CorrelationVector::parse(&input).unwrap());
+        assert_eq!(
+            shared.next_outbound().as_str(),
+            format!("AP9v1ltK2S7qTS77z0lWtKg{}.9", ".0".repeat(51))
+        );
+
+        let reset = shared.next_outbound();
+        assert!(reset.reset_marker().is_some());
+        assert!(!reset.is_immutable());
+        let next = shared.next_outbound();
+        assert_eq!(next.reset_marker(), reset.reset_marker());
+        assert_eq!(
+            next.segments().last().unwrap(),
+            reset.segments().last().unwrap() + 1
+        );
+        assert_eq!(shared.into_inner(), next);
+    }
+
+    #[test]
+    fn current_counts_claimed_increments() {
+        let shared = SharedCorrelationVector::new(
+            CorrelationVector::parse_strict("P9v1ltK2S7qTS77z0lWtKg.1.9").unwrap(),
+        );
+        assert_eq!(shared.current().as_str(), "P9v1ltK2S7qTS77z0lWtKg.1.9");
+        shared.next_outbound();
+        shared.next_outbound();
+        assert_eq!(shared.current().as_str(), "P9v1ltK2S7qTS77z0lWtKg.1.11");
+
+        let terminated = CorrelationVector::parse("P9v1ltK2S7qTS77z0lWtKg.1!").unwrap();
+        let shared = SharedCorrelationVector::new(terminated.clone());
+        assert_eq!(shared.next_outbound(), terminated);
+        assert_eq!(shared.current(), terminated);
+    }
+}
@@ -0,0 +1,281 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    convert::TryFrom,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[cfg(feature = "std")]
+use crate::spinparams::parse_env;
+use crate::{
+    spinparams::{SpinCounterInterval, SpinCounterPeriodicity, SpinEntropy, SpinParams},
+    spinparamserror::SpinParamsError,
+    spinparamsparseerror::SpinParamsParseError,
+};
+
+/// The bit layout of a spun value: how many bits are dropped from the timestamp, how many of the
+/// following bits are kept as a counter and how many random bits follow them.
+///
+/// Every [`SpinParams`] converts into a layout, and [`SpinLayout::new`] creates layouts with
+/// explicit bit widths, e.g. finer than [`SpinCounterInterval::Fine`] with fewer digits than
+/// [`SpinParams::PRECISE`]:
+/// ```rust
+/// # #[cfg(feature = "std")] {
+/// use cvlib::{CorrelationVector, SpinLayout, SpinParams};
+///
+/// let mut cv = CorrelationVector::new();
+/// cv.spin(SpinParams::default());
+/// cv.spin(SpinLayout::new(20, 20, 4).unwrap());
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct SpinLayout {
+    ticks_to_drop: u8,
+    counter_bits: u8,
+    entropy_bits: u8,
+}
+
+impl SpinLayout {
+    /// Drop `ticks_to_drop` bits from the timestamp, keep the next `counter_bits` bits and
+    /// follow them with `entropy_bits` random bits.
+    ///
+    /// At most 63 bits can be dropped, and counter and entropy bits must fit in the 64 bits of a
+    /// spun value.
+    pub fn new(
+        ticks_to_drop: u8,
+        counter_bits: u8,
+        entropy_bits: u8,
+    ) -> Result<SpinLayout, SpinParamsError> {
+        if ticks_to_drop >= 64 {
+            return Err(SpinParamsError::TooManyTicksToDrop {
+                bits: u32::from(ticks_to_drop),
+            });
+        }
+        let bits = u32::from(counter_bits) + u32::from(entropy_bits);
+        if bits > 64 {
+            return Err(SpinParamsError::TooManyBits { bits });
+        }
+        Ok(SpinLayout {
+            ticks_to_drop,
+            counter_bits,
+            entropy_bits,
+        })
+    }
+
+    /// Read a layout in its string form, e.g. `20/20/4` or `coarse/short/two`, from an
+    /// environment variable. Returns `None` if the variable is not set.
+    #[cfg(feature = "std")]
+    pub fn from_env(key: &str) -> Result<Option<SpinLayout>, SpinParamsParseError> {
+        parse_env(key)
+    }
+
+    /// The number of bits dropped from the timestamp
+    pub fn ticks_to_drop(&self) -> u8 {
+        self.ticks_to_drop
+    }
+
+    /// The number of timestamp bits kept as a counter
+    pub fn counter_bits(&self) -> u8 {
+        self.counter_bits
+    }
+
+    /// The number of random bits following the counter
+    pub fn entropy_bits(&self) -> u8 {
+        self.entropy_bits
+    }
+
+    /// The number of bits of a spun value, at most 64
+    pub(crate) fn value_bits(&self) -> u32 {
+        u32::from(self.counter_bits) + u32::from(self.entropy_bits)
+    }
+
+    /// The number of segments a spun value takes up in the vector clock
+    pub(crate) fn segment_count(&self) -> usize {
+        if self.value_bits() > 32 {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+impl From<SpinParams> for SpinLayout {
+    fn from(params: SpinParams) -> Self {
+        let ticks_to_drop = match params.spin_counter_interval {
+            SpinCounterInterval::Coarse => 24,
+            SpinCounterInterval::Fine => 16,
+        };
+        let counter_bits = match params.spin_counter_periodicity {
+            SpinCounterPeriodicity::None => 0,
+            SpinCounterPeriodicity::Short => 16,
+            SpinCounterPeriodicity::Medium => 24,
+            SpinCounterPeriodicity::Long => 32,
+        };
+        let entropy_bits = match params.spin_entropy {
+            SpinEntropy::None => 0,
+            SpinEntropy::One => 8,
+            SpinEntropy::Two => 16,
+            SpinEntropy::Three => 24,
+            SpinEntropy::Four => 32,
+        };
+        SpinLayout {
+            ticks_to_drop,
+            counter_bits,
+            entropy_bits,
+        }
+    }
+}
+
+impl Default for SpinLayout {
+    fn default() -> Self {
+        SpinParams::DEFAULT.into()
+    }
+}
+
+/// Formats the layout as `ticks_to_drop/counter_bits/entropy_bits`, e.g. `20/20/4`
+impl Display for SpinLayout {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), core::fmt::Error> {
+        write!(
+            f,
+            "{}/{}/{}",
+            self.ticks_to_drop, self.counter_bits, self.entropy_bits
+        )
+    }
+}
+
+/// Parses the form written by [`Display`] or the string form of [`SpinParams`], e.g.
+/// `coarse/short/two`
+impl FromStr for SpinLayout {
+    type Err = SpinParamsParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let components = s.split('/').map(str::trim).collect::<Vec<_>>();
+        let bits = match components
+            .iter()
+            .map(|component| component.parse::<u8>())
+            .collect::<Result<Vec<u8>, _>>()
+        {
+            Ok(bits) => bits,
+            Err(_) => return s.parse::<SpinParams>().map(SpinLayout::from),
+        };
+        match *bits {
+            [ticks_to_drop, counter_bits, entropy_bits] => {
+                Ok(SpinLayout::new(ticks_to_drop, counter_bits, entropy_bits)?)
+            }
+            [_, _, _, ..] => Err(SpinParamsParseError::InvalidComponent {
+                component: components[3].to_string(),
+            }),
+            _ => Err(SpinParamsParseError::MissingComponent),
+        }
+    }
+}
+
+impl TryFrom<String> for SpinLayout {
+    type Error = SpinParamsParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<SpinLayout> for String {
+    fn from(layout: SpinLayout) -> Self {
+        layout.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_match_reference() {
+        assert_eq!(
+            SpinLayout::from(SpinParams::DEFAULT),
+            SpinLayout::new(24, 16, 16).unwrap()
+        );
+        assert_eq!(
+            SpinLayout::from(SpinParams::PRECISE),
+            SpinLayout::new(16, 32, 32).unwrap()
+        );
+        assert_eq!(SpinLayout::default().segment_count(), 1);
+        assert_eq!(SpinLayout::from(SpinParams::PRECISE).segment_count(), 2);
+    }
+
+    #[test]
+    fn new_rejects_out_of_range_widths() {
+        assert_eq!(
+            SpinLayout::new(64, 16, 16),
+            Err(SpinParamsError::TooManyTicksToDrop { bits: 64 })
+        );
+        assert_eq!(
+            SpinLayout::new(u8::MAX, 0, 0),
+            Err(SpinParamsError::TooManyTicksToDrop { bits: 255 })
+        );
+        assert_eq!(
+            SpinLayout::new(16, 40, 32),
+            Err(SpinParamsError::TooManyBits { bits: 72 })
+        );
+        assert_eq!(
+            SpinLayout::new(0, u8::MAX, u8::MAX),
+            Err(SpinParamsError::TooManyBits { bits: 510 })
+        );
+        assert!(SpinLayout::new(63, 32, 32).is_ok());
+        assert!(SpinLayout::new(0, 0, 64).is_ok());
+    }
+
+    #[test]
+    fn string_form_round_trips() {
+        let layout = SpinLayout::new(20, 20, 4).unwrap();
+        assert_eq!(layout.to_string(), "20/20/4");
+        assert_eq!(" 20 / 20 / 4 ".parse::<SpinLayout>(), Ok(layout));
+        assert_eq!(
+            "fine/long/four".parse::<SpinLayout>(),
+            Ok(SpinParams::PRECISE.into())
+        );
+    }
+
+    #[test]
+    fn string_form_rejects_invalid_input() {
+        assert_eq!(
+            "16/40/32".parse::<SpinLayout>(),
+            Err(SpinParamsParseError::Invalid {
+                source: SpinParamsError::TooManyBits { bits: 72 }
+            })
+        );
+        assert_eq!(
+            "20/20".parse::<SpinLayout>(),
+            Err(SpinParamsParseError::MissingComponent)
+        );
+        assert_eq!(
+            "20/20/4/1".parse::<SpinLayout>(),
+            Err(SpinParamsParseError::InvalidComponent {
+                component: "1".to_string()
+            })
+        );
+        assert_eq!(
+            "coarse/tiny/two".parse::<SpinLayout>(),
+            Err(SpinParamsParseError::InvalidComponent {
+                component: "tiny".to_string()
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips() {
+        let layout = SpinLayout::new(20, 20, 4).unwrap();
+        let json = serde_json::to_string(&layout).unwrap();
+        assert_eq!(json, r#""20/20/4""#);
+        assert_eq!(serde_json::from_str::<SpinLayout>(&json).unwrap(), layout);
+        assert!(serde_json::from_str::<SpinLayout>(r#""16/40/32""#).is_err());
+    }
+}
@@ -1,16 +1,160 @@
+use alloc::{string::ToString, vec::Vec};
+use core::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+#[cfg(feature = "std")]
+use std::env;
+
+use crate::{
+    entropysource::EntropySource,
+    spinlayout::SpinLayout,
+    spinparamsparseerror::SpinParamsParseError,
+    sync::{lock, Mutex},
+};
+
 /// The parameters for the spin operation
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct SpinParams {
     /// The number of ticks to drop from the UTC timestamp
     pub spin_counter_interval: SpinCounterInterval,
     /// The number of bits to use from the UTC timestamp
     pub spin_counter_periodicity: SpinCounterPeriodicity,
-    /// How many entropy bytes to use
+    /// How much entropy to use
     pub spin_entropy: SpinEntropy,
 }
 
+impl SpinParams {
+    /// The default spin parameters of the reference implementation: drop 24 bits, keep 16 bits
+    /// of the timestamp and add 2 bytes of entropy. The value fits in a single segment.
+    pub const DEFAULT: SpinParams = SpinParams {
+        spin_counter_interval: SpinCounterInterval::Coarse,
+        spin_counter_periodicity: SpinCounterPeriodicity::Short,
+        spin_entropy: SpinEntropy::Two,
+    };
+
+    /// The shortest spun values: 16 bits of the timestamp and no entropy, at most 5 digits.
+    /// Only suitable together with
+    /// [`CorrelationVector::with_monotonic_spin`](crate::CorrelationVector::with_monotonic_spin())
+    /// or when spins are rare.
+    pub const COMPACT: SpinParams = SpinParams {
+        spin_entropy: SpinEntropy::None,
+        ..SpinParams::DEFAULT
+    };
+
+    /// The most unique spun values: drop 16 bits, keep 32 bits of the timestamp and add 4 bytes
+    /// of entropy, taking up two segments
+    pub const PRECISE: SpinParams = SpinParams {
+        spin_counter_interval: SpinCounterInterval::Fine,
+        spin_counter_periodicity: SpinCounterPeriodicity::Long,
+        spin_entropy: SpinEntropy::Four,
+    };
+
+    /// Read spin parameters in their string form, e.g. `coarse/short/two`, from an environment
+    /// variable. Returns `None` if the variable is not set.
+    #[cfg(feature = "std")]
+    pub fn from_env(key: &str) -> Result<Option<SpinParams>, SpinParamsParseError> {
+        parse_env(key)
+    }
+}
+
+/// Parse the environment variable `key`, if it is set
+#[cfg(feature = "std")]
+pub(crate) fn parse_env<T: FromStr<Err = SpinParamsParseError>>(
+    key: &str,
+) -> Result<Option<T>, SpinParamsParseError> {
+    match env::var(key) {
+        Ok(value) => value.parse().map(Some),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(_)) => Err(SpinParamsParseError::NotUnicode),
+    }
+}
+
+impl Default for SpinParams {
+    fn default() -> Self {
+        SpinParams::DEFAULT
+    }
+}
+
+/// Formats the parameters as `interval/periodicity/entropy`, e.g. `coarse/short/two`
+impl Display for SpinParams {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), core::fmt::Error> {
+        match self.spin_counter_interval {
+            SpinCounterInterval::Coarse => write!(f, "coarse"),
+            SpinCounterInterval::Fine => write!(f, "fine"),
+        }?;
+        match self.spin_counter_periodicity {
+            SpinCounterPeriodicity::None => write!(f, "/none"),
+            SpinCounterPeriodicity::Short => write!(f, "/short"),
+            SpinCounterPeriodicity::Medium => write!(f, "/medium"),
+            SpinCounterPeriodicity::Long => write!(f, "/long"),
+        }?;
+        match self.spin_entropy {
+            SpinEntropy::None => write!(f, "/none"),
+            SpinEntropy::One => write!(f, "/one"),
+            SpinEntropy::Two => write!(f, "/two"),
+            SpinEntropy::Three => write!(f, "/three"),
+            SpinEntropy::Four => write!(f, "/four"),
+        }
+    }
+}
+
+/// Parses the form written by [`Display`], ignoring case
+impl FromStr for SpinParams {
+    type Err = SpinParamsParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components = s.split('/').map(str::trim);
+        let mut next = || {
+            components
+                .next()
+                .ok_or(SpinParamsParseError::MissingComponent)
+        };
+        let invalid = |component: &str| SpinParamsParseError::InvalidComponent {
+            component: component.to_string(),
+        };
+
+        let interval = next()?;
+        let spin_counter_interval = match interval.to_ascii_lowercase().as_str() {
+            "coarse" => SpinCounterInterval::Coarse,
+            "fine" => SpinCounterInterval::Fine,
+            _ => return Err(invalid(interval)),
+        };
+        let periodicity = next()?;
+        let spin_counter_periodicity = match periodicity.to_ascii_lowercase().as_str() {
+            "none" => SpinCounterPeriodicity::None,
+            "short" => SpinCounterPeriodicity::Short,
+            "medium" => SpinCounterPeriodicity::Medium,
+            "long" => SpinCounterPeriodicity::Long,
+            _ => return Err(invalid(periodicity)),
+        };
+        let entropy = next()?;
+        let spin_entropy = match entropy.to_ascii_lowercase().as_str() {
+            "none" => SpinEntropy::None,
+            "one" => SpinEntropy::One,
+            "two" => SpinEntropy::Two,
+            "three" => SpinEntropy::Three,
+            "four" => SpinEntropy::Four,
+            _ => return Err(invalid(entropy)),
+        };
+
+        if let Some(extra) = components.next() {
+            return Err(invalid(extra));
+        }
+
+        Ok(SpinParams {
+            spin_counter_interval,
+            spin_counter_periodicity,
+            spin_entropy,
+        })
+    }
+}
+
 /// The number of ticks to drop from the UTC timestamp
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpinCounterInterval {
     /// Drop 24 bits
     Coarse,
@@ -18,15 +162,9 @@ pub enum SpinCounterInterval {
     Fine,
 }
 
-pub(crate) fn ticks_to_drop(interval: SpinCounterInterval) -> u64 {
-    match interval {
-        SpinCounterInterval::Coarse => 24,
-        SpinCounterInterval::Fine => 16,
-    }
-}
-
 /// The number of bits to use from the UTC timestamp
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpinCounterPeriodicity {
     None,
     /// use 16 bits
@@ -37,19 +175,9 @@ pub enum SpinCounterPeriodicity {
     Long,
 }
 
-pub(crate) fn tick_periodicity_bits(params: SpinParams) -> u64 {
-    let counter_bits = match params.spin_counter_periodicity {
-        SpinCounterPeriodicity::None => 0,
-        SpinCounterPeriodicity::Short => 16,
-        SpinCounterPeriodicity::Medium => 24,
-        SpinCounterPeriodicity::Long => 32,
-    };
-
-    counter_bits + entropy_bytes(params.spin_entropy) * 8
-}
-
 /// How many entropy bytes to use
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpinEntropy {
     None,
     One,
@@ -58,22 +186,195 @@ pub enum SpinEntropy {
     Four,
 }
 
-pub(crate) fn entropy_bytes(entropy: SpinEntropy) -> u64 {
-    match entropy {
-        SpinEntropy::None => 0,
-        SpinEntropy::One => 1,
-        SpinEntropy::Two => 2,
-        SpinEntropy::Three => 3,
-        SpinEntropy::Four => 4,
+/// Which timestamp and segment order a [`CorrelationVector`](crate::CorrelationVector) spins
+/// with, see [`CorrelationVector::with_spin_mode`](crate::CorrelationVector::with_spin_mode())
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpinMode {
+    /// Count 100ns ticks since the UNIX epoch. A value wider than 32 bits is split into its low
+    /// 32 bits followed by its high 32 bits.
+    Unix,
+    /// Count 100ns ticks since 0001-01-01 like .NET's `DateTime.UtcNow.Ticks`. A value wider than
+    /// 32 bits is split into its high 32 bits followed by its low 32 bits.
+    /// This matches the reference implementation, so spun values sort consistently with it.
+    Reference,
+}
+
+impl Default for SpinMode {
+    /// [`SpinMode::Unix`], which earlier versions of this crate always used
+    fn default() -> Self {
+        SpinMode::Unix
+    }
+}
+
+/// The number of 100ns ticks between 0001-01-01 and the UNIX epoch
+const REFERENCE_EPOCH_OFFSET_TICKS: u128 = 621_355_968_000_000_000;
+
+pub(crate) fn epoch_offset_ticks(mode: SpinMode) -> u128 {
+    match mode {
+        SpinMode::Unix => 0,
+        SpinMode::Reference => REFERENCE_EPOCH_OFFSET_TICKS,
+    }
+}
+
+/// `bits` random bits from `source`
+pub(crate) fn generate_entropy(bits: u32, source: &mut dyn EntropySource) -> u64 {
+    let mut bytes = [0; 8];
+    let bytes = &mut bytes[..bits.div_ceil(8) as usize];
+    source.fill_bytes(bytes);
+
+    let value = bytes
+        .iter()
+        .fold(0, |value, &byte| (value << 8) | u64::from(byte));
+    value & low_bits_mask(bits)
+}
+
+/// A mask of the lowest `bits` bits
+pub(crate) fn low_bits_mask(bits: u32) -> u64 {
+    if bits >= 64 {
+        u64::MAX
+    } else {
+        (1 << bits) - 1
     }
 }
 
-pub(crate) fn generate_entropy(entropy: SpinEntropy) -> Vec<u8> {
-    let bytes_to_generate = entropy_bytes(entropy);
+/// The latest value spun with each bit layout, used by
+/// [`CorrelationVector::with_monotonic_spin`](crate::CorrelationVector::with_monotonic_spin())
+static LAST_MONOTONIC_SPINS: Mutex<Vec<(SpinLayout, SpinMode, u64)>> = Mutex::new(Vec::new());
+
+/// Make `value` sort strictly after the previous value spun with the same bit layout.
+///
+/// Values only keep the low bits of the timestamp and wrap around, so they are compared with
+/// serial number arithmetic: a value up to half the range behind the previous one is a
+/// collision or clock skew and is bumped, anything further behind has wrapped around.
+pub(crate) fn next_monotonic(layout: SpinLayout, mode: SpinMode, value: u64) -> u64 {
+    let mask = low_bits_mask(layout.value_bits());
+
+    let mut last_spins = lock(&LAST_MONOTONIC_SPINS);
+    let last = match last_spins
+        .iter_mut()
+        .find(|(l, m, _)| *l == layout && *m == mode)
+    {
+        Some((_, _, last)) => last,
+        None => {
+            last_spins.push((layout, mode, value));
+            return value;
+        }
+    };
+
+    let behind = last.wrapping_sub(value) & mask;
+    let value = if behind <= mask / 2 {
+        last.wrapping_add(1) & mask
+    } else {
+        value
+    };
+    *last = value;
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_matches_reference() {
+        let params = SpinParams::default();
+        assert_eq!(params.spin_counter_interval, SpinCounterInterval::Coarse);
+        assert_eq!(
+            params.spin_counter_periodicity,
+            SpinCounterPeriodicity::Short
+        );
+        assert_eq!(params.spin_entropy, SpinEntropy::Two);
+    }
+
+    #[test]
+    fn string_form_round_trips() {
+        for (input, params) in [
+            ("coarse/short/two", SpinParams::DEFAULT),
+            ("coarse/short/none", SpinParams::COMPACT),
+            ("fine/long/four", SpinParams::PRECISE),
+            (
+                "fine/medium/one",
+                SpinParams {
+                    spin_counter_interval: SpinCounterInterval::Fine,
+                    spin_counter_periodicity: SpinCounterPeriodicity::Medium,
+                    spin_entropy: SpinEntropy::One,
+                },
+            ),
+        ] {
+            assert_eq!(input.parse::<SpinParams>(), Ok(params));
+            assert_eq!(params.to_string(), input);
+        }
+        assert_eq!(
+            " Coarse / SHORT / two ".parse::<SpinParams>(),
+            Ok(SpinParams::DEFAULT)
+        );
+    }
+
+    #[test]
+    fn string_form_rejects_invalid_input() {
+        assert_eq!(
+            "coarse/short".parse::<SpinParams>(),
+            Err(SpinParamsParseError::MissingComponent)
+        );
+        assert_eq!(
+            "coarse/tiny/two".parse::<SpinParams>(),
+            Err(SpinParamsParseError::InvalidComponent {
+                component: "tiny".to_string()
+            })
+        );
+        assert_eq!(
+            "coarse/short/two/fast".parse::<SpinParams>(),
+            Err(SpinParamsParseError::InvalidComponent {
+                component: "fast".to_string()
+            })
+        );
+        assert_eq!(
+            "20/20/4".parse::<SpinParams>(),
+            Err(SpinParamsParseError::InvalidComponent {
+                component: "20".to_string()
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips() {
+        let json = serde_json::to_string(&SpinParams::PRECISE).unwrap();
+        assert_eq!(
+            json,
+            r#"{"spin_counter_interval":"Fine","spin_counter_periodicity":"Long","spin_entropy":"Four"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<SpinParams>(&json).unwrap(),
+            SpinParams::PRECISE
+        );
+
+        let params = serde_json::from_str::<SpinParams>(
+            r#"{"spin_counter_interval":"Fine","spin_entropy":"Four"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            params,
+            SpinParams {
+                spin_counter_interval: SpinCounterInterval::Fine,
+                spin_entropy: SpinEntropy::Four,
+                ..SpinParams::default()
+            }
+        );
+    }
 
-    let mut result = Vec::new();
-    for _ in 0..bytes_to_generate {
-        result.push(rand::random::<u8>());
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_env() {
+        env::set_var("CVLIB_TEST_SPIN_PARAMS", "fine/long/four");
+        assert_eq!(
+            SpinParams::from_env("CVLIB_TEST_SPIN_PARAMS"),
+            Ok(Some(SpinParams::PRECISE))
+        );
+        assert_eq!(
+            SpinParams::from_env("CVLIB_TEST_SPIN_PARAMS_UNSET"),
+            Ok(None)
+        );
     }
-    result
 }
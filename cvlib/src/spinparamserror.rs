@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+/// The error type for [`SpinLayout::new`](super::SpinLayout::new())
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum SpinParamsError {
+    /// Dropping this many bits leaves nothing of the timestamp
+    #[error("Can not drop {bits} bits from the timestamp, at most 63 can be dropped")]
+    TooManyTicksToDrop { bits: u32 },
+    /// The counter and entropy bits do not fit in the 64 bits of a spun value
+    #[error("Counter and entropy use {bits} bits, but a spun value has at most 64")]
+    TooManyBits { bits: u32 },
+}
@@ -0,0 +1,26 @@
+use alloc::string::String;
+
+use thiserror::Error;
+
+use crate::spinparamserror::SpinParamsError;
+
+/// The error type for parsing [`SpinParams`](super::SpinParams) or
+/// [`SpinLayout`](super::SpinLayout) from a string such as `coarse/short/two`
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum SpinParamsParseError {
+    /// The string does not have the interval, periodicity and entropy components
+    #[error("Spin parameters need an interval, a periodicity and an entropy")]
+    MissingComponent,
+    /// A component is not one of the known values
+    #[error("Invalid spin parameter '{component}'")]
+    InvalidComponent { component: String },
+    /// The components are valid on their own, but not together
+    #[error("Invalid spin parameters")]
+    Invalid {
+        #[from]
+        source: SpinParamsError,
+    },
+    /// The environment variable holds invalid unicode
+    #[error("Environment variable holding spin parameters is not valid unicode")]
+    NotUnicode,
+}
@@ -0,0 +1,153 @@
+//! The structured serde form of a [`CorrelationVector`], for storage formats that keep its parts
+//! in separate fields. Use it with `#[serde(with = "cvlib::structured")]`:
+//! ```rust
+//! # use serde::{Deserialize, Serialize};
+//! use cvlib::CorrelationVector;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "cvlib::structured")]
+//!     cv: CorrelationVector,
+//! }
+//! ```
+//! A correlation vector such as `AP9v1ltK2S7qTS77z0lWtKg#1f.1.2!` is written as its
+//! `version`, `base`, `reset`, `vector` and `immutable` parts. The parts are validated as
+//! [`CorrelationVector::parse_strict`] validates the string representation.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use arrayvec::ArrayString;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    correlationvector::{CorrelationVector, MAX_INPUT_LENGTH},
+    correlationvectorversion::CorrelationVectorVersion,
+};
+
+#[derive(Serialize)]
+struct Parts<'a, V> {
+    version: CorrelationVectorVersion,
+    base: &'a str,
+    reset: Option<u64>,
+    vector: V,
+    immutable: bool,
+}
+
+#[derive(Deserialize)]
+struct OwnedParts {
+    version: CorrelationVectorVersion,
+    base: String,
+    #[serde(default)]
+    reset: Option<u64>,
+    vector: Vec<u32>,
+    #[serde(default)]
+    immutable: bool,
+}
+
+/// A sequence of the segments of a vector clock, serialized without collecting them
+struct Segments<'a>(&'a CorrelationVector);
+
+impl Serialize for Segments<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.segments())
+    }
+}
+
+/// Serialize a [`CorrelationVector`] as its parts
+pub fn serialize<S: Serializer>(cv: &CorrelationVector, serializer: S) -> Result<S::Ok, S::Error> {
+    Parts {
+        version: cv.version(),
+        base: cv.base(),
+        reset: cv.reset_marker(),
+        vector: Segments(cv),
+        immutable: cv.is_immutable(),
+    }
+    .serialize(serializer)
+}
+
+/// Deserialize a [`CorrelationVector`] from its parts
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<CorrelationVector, D::Error> {
+    let parts = OwnedParts::deserialize(deserializer)?;
+
+    let mut text = ArrayString::<MAX_INPUT_LENGTH>::new();
+    let mut write = || -> core::fmt::Result {
+        text.try_push_str(&parts.base)
+            .map_err(|_| core::fmt::Error)?;
+        if let Some(reset) = parts.reset {
+            write!(text, "#{:x}", reset)?;
+        }
+        for segment in &parts.vector {
+            write!(text, ".{}", segment)?;
+        }
+        if parts.immutable {
+            text.try_push('!').map_err(|_| core::fmt::Error)?;
+        }
+        Ok(())
+    };
+    if write().is_err() {
+        return Err(de::Error::custom(
+            "String is too long to be a valid correlation vector",
+        ));
+    }
+
+    let cv = CorrelationVector::parse_strict(&text).map_err(de::Error::custom)?;
+    if cv.version() != parts.version {
+        return Err(de::Error::custom(format_args!(
+            "Base of a {:?} correlation vector has the length of a {:?} base",
+            parts.version,
+            cv.version()
+        )));
+    }
+    Ok(cv)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::CorrelationVector;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        #[serde(with = "crate::structured")]
+        cv: CorrelationVector,
+    }
+
+    #[test]
+    fn round_trips_parts() {
+        let cv = CorrelationVector::parse_strict("AP9v1ltK2S7qTS77z0lWtKg#1f.1.2!").unwrap();
+        let event = Event { cv };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            json,
+            r#"{"cv":{"version":"V3","base":"AP9v1ltK2S7qTS77z0lWtKg","reset":31,"vector":[1,2],"immutable":true}}"#
+        );
+        assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn validates_parts() {
+        let event = serde_json::from_str::<Event>(
+            r#"{"cv":{"version":"V2","base":"P9v1ltK2S7qTS77z0lWtKg","vector":[0]}}"#,
+        )
+        .unwrap();
+        assert_eq!(event.cv.to_string(), "P9v1ltK2S7qTS77z0lWtKg.0");
+
+        for json in [
+            r#"{"cv":{"version":"V1","base":"P9v1ltK2S7qTS77z0lWtKg","vector":[0]}}"#,
+            r#"{"cv":{"version":"V2","base":"P9v1ltK2S7qTS77z0lWtKg","vector":[]}}"#,
+            r#"{"cv":{"version":"V2","base":"P9v1ltK2S7qTS77z0lWt-g","vector":[0]}}"#,
+            r#"{"cv":{"version":"V2","base":"P9v1ltK2S7qTS77z0lWtKg","reset":1,"vector":[0]}}"#,
+        ] {
+            assert!(serde_json::from_str::<Event>(json).is_err(), "{}", json);
+        }
+        let too_long = format!(
+            r#"{{"cv":{{"version":"V2","base":"P9v1ltK2S7qTS77z0lWtKg","vector":[{}]}}}}"#,
+            ["4294967295"; 12].join(",")
+        );
+        assert!(serde_json::from_str::<Event>(&too_long).is_err());
+    }
+}
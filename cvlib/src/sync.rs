@@ -0,0 +1,169 @@
+//! The lock used for state shared between threads: the standard library's mutex with `std`, a
+//! spin lock without it, as there is no operating system to wait on.
+
+#[cfg(not(feature = "std"))]
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug, Formatter},
+    hint,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, Ordering},
+};
+#[cfg(feature = "std")]
+pub(crate) use std::sync::{Mutex, MutexGuard};
+
+/// A spin lock with the parts of the standard library's mutex the crate uses
+#[cfg(not(feature = "std"))]
+pub(crate) struct Mutex<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: the value is only reachable through a `MutexGuard`, and `lock` hands out one guard at
+// a time. Sharing the mutex therefore only lets threads take turns with the value, as if it was
+// sent between them, which `T: Send` allows.
+#[cfg(not(feature = "std"))]
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+#[cfg(not(feature = "std"))]
+impl<T> Mutex<T> {
+    pub(crate) const fn new(value: T) -> Mutex<T> {
+        Mutex {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, T> {
+        // Acquire pairs with the Release in `MutexGuard::drop`, so the previous guard's writes
+        // to the value are visible to the new one
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            while self.locked.load(Ordering::Relaxed) {
+                hint::spin_loop();
+            }
+        }
+        MutexGuard {
+            mutex: self,
+            _value: PhantomData,
+        }
+    }
+
+    fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: Debug> Debug for Mutex<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mutex")
+            .field("value", &*self.lock())
+            .finish()
+    }
+}
+
+/// Exclusive access to the value of a locked [`Mutex`], unlocking it when dropped
+#[cfg(not(feature = "std"))]
+pub(crate) struct MutexGuard<'a, T> {
+    mutex: &'a Mutex<T>,
+    /// Makes the guard `Send` and `Sync` only as `&mut T` is, as it hands out references to
+    /// the value
+    _value: PhantomData<&'a mut T>,
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the guard exists only while it holds the lock, so the value is not mutated
+        // through any other reference for as long as the returned reference borrows the guard
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the guard exists only while it holds the lock, and the returned reference
+        // borrows the guard mutably, so it is the only reference to the value
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+    }
+}
+
+/// Lock `mutex`, ignoring poisoning: the state is valid after every update
+#[cfg(feature = "std")]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Lock `mutex`
+#[cfg(not(feature = "std"))]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock()
+}
+
+/// Take the value out of `mutex`, ignoring poisoning
+#[cfg(feature = "std")]
+pub(crate) fn into_inner<T>(mutex: Mutex<T>) -> T {
+    mutex
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Take the value out of `mutex`
+#[cfg(not(feature = "std"))]
+pub(crate) fn into_inner<T>(mutex: Mutex<T>) -> T {
+    mutex.into_inner()
+}
+
+#[cfg(all(test, not(feature = "std")))]
+mod tests {
+    extern crate std;
+
+    use alloc::{sync::Arc, vec::Vec};
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn lock_is_exclusive_under_contention() {
+        let mutex = Arc::new(Mutex::new((0u64, 0u64)));
+        let threads = (0..8)
+            .map(|_| {
+                let mutex = Arc::clone(&mutex);
+                thread::spawn(move || {
+                    for _ in 0..10_000 {
+                        let mut guard = lock(&mutex);
+                        // a second writer inside the lock would let the halves drift apart
+                        let (first, second) = *guard;
+                        assert_eq!(first, second);
+                        guard.0 = first + 1;
+                        hint::spin_loop();
+                        guard.1 = second + 1;
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let mutex = Arc::try_unwrap(mutex).unwrap();
+        assert_eq!(into_inner(mutex), (80_000, 80_000));
+    }
+}
@@ -0,0 +1,8 @@
+//! Helpers shared by the tests of several modules
+
+use crate::correlationvector::CorrelationVector;
+
+/// Parse a correlation vector the test expects to be valid
+pub(crate) fn cv(input: &str) -> CorrelationVector {
+    CorrelationVector::parse_strict(input).unwrap()
+}